@@ -0,0 +1,54 @@
+//! cost of the handshake steps per group, the hash is part of every step,
+//! `multiplier` is `k = H(N | PAD(g))` alone, a candidate for caching
+//!
+//! run with `cargo bench --bench handshake`
+use criterion::{criterion_group, criterion_main, BenchmarkId, Criterion};
+use srp6::*;
+
+const USERNAME: UsernameRef = "Bob";
+const USER_PASSWORD: &ClearTextPassword = "secret-password";
+
+fn bench_group<const KEY_LENGTH: usize, const SALT_LENGTH: usize>(
+    c: &mut Criterion,
+    constants: &OpenConstants,
+) {
+    // e.g. `Srp6(1024-bit, SHA1, 128 byte salt)`
+    let id = Srp6::<KEY_LENGTH, SALT_LENGTH>::new().to_string();
+    let mut group = c.benchmark_group("handshake");
+
+    group.bench_function(BenchmarkId::new("multiplier", &id), |b| {
+        let host = Srp6::<KEY_LENGTH, SALT_LENGTH>::new();
+        b.iter(|| host.multiplier(constants))
+    });
+
+    group.bench_function(BenchmarkId::new("start_handshake", &id), |b| {
+        let mut user = Srp6User::<KEY_LENGTH, SALT_LENGTH>::new();
+        b.iter(|| user.start_handshake(USERNAME, constants))
+    });
+
+    let mut host = Srp6::<KEY_LENGTH, SALT_LENGTH>::new();
+    let mut user = Srp6User::<KEY_LENGTH, SALT_LENGTH>::new();
+    let details = host.generate_new_user_secrets(USERNAME, USER_PASSWORD, constants);
+    let user_handshake = user.start_handshake(USERNAME, constants);
+    let server_handshake = host
+        .continue_handshake(&details, &user_handshake, constants)
+        .unwrap();
+    let proof = user
+        .update_handshake(&server_handshake, constants, USERNAME, USER_PASSWORD)
+        .unwrap();
+
+    group.bench_function(BenchmarkId::new("verify_proof", &id), |b| {
+        b.iter(|| host.verify_proof(&proof).unwrap())
+    });
+
+    group.finish();
+}
+
+fn handshake(c: &mut Criterion) {
+    bench_group::<128, 128>(c, &rfc5054_1024());
+    bench_group::<256, 256>(c, &rfc5054_2048());
+    bench_group::<512, 512>(c, &rfc5054_4096());
+}
+
+criterion_group!(benches, handshake);
+criterion_main!(benches);
@@ -1,16 +1,17 @@
 use srp6::*;
-use std::time::{Duration, Instant};
+use std::time::Instant;
 
 fn main() {
     // this is what a user would enter in a form / terminal
     let new_username: UsernameRef = "Bob";
     let user_password: &ClearTextPassword = "secret-password";
-    
+
     let mut srp6 = Srp6_4096::new();
 
     let start = Instant::now();
 
-    let user_details = srp6.generate_new_user_secrets(new_username, user_password, &get_constants());
+    let user_details =
+        srp6.generate_new_user_secrets(new_username, user_password, &get_constants());
     assert_eq!(user_details.salt.num_bytes(), 4096 / 8);
     assert_eq!(user_details.verifier.num_bytes(), 4096 / 8);
 
@@ -1,11 +1,10 @@
 use srp6::*;
-use std::time::{Instant, Duration};
 use std::str::FromStr;
+use std::time::{Duration, Instant};
 
 const USER_PASSWORD: &ClearTextPassword = "secret-password";
 
 fn main() {
-
     // let user = mocked::lookup_user_details("Bob");
     let username = String::from_str("Bob").unwrap();
     let constants = get_constants();
@@ -18,34 +17,22 @@ fn main() {
         // user creates a handshake
         let user_handshake = srp6_user.start_handshake(&username, &constants);
 
-        let server_handshake = srp6.continue_handshake(
-            &user_details,
-            &user_handshake,
-            &constants
-        ).unwrap();
-
-        let proof = srp6_user.update_handshake(
-            &server_handshake,
-            &constants,
-            &username, 
-            USER_PASSWORD,
-        ).unwrap();
-
-        let hamk = match srp6.verify_proof(
-            &proof
-        ){
-            Err(_er) => { Proof::default()},
-            Ok(value) => value
-        };
+        let server_handshake = srp6
+            .continue_handshake(&user_details, &user_handshake, &constants)
+            .unwrap();
 
+        let proof = srp6_user
+            .update_handshake(&server_handshake, &constants, &username, USER_PASSWORD)
+            .unwrap();
 
-        assert!(srp6_user.verify_proof(&hamk));
+        let hamk = srp6.verify_proof(&proof).unwrap_or_default();
 
+        assert!(srp6_user.verify_proof(&hamk).is_ok());
 
         let duration = start.elapsed();
 
         durations = durations.checked_add(duration).unwrap()
     }
 
-    println!("Time elapsed in auth is: {:?}", durations/500);
+    println!("Time elapsed in auth is: {:?}", durations / 500);
 }
@@ -0,0 +1,81 @@
+//! writes the values this crate computes for fixed inputs as a JSON fixture,
+//! e.g. to test implementations in other languages against it:
+//!
+//! `cargo run --example export_vectors --features testing -- vectors.json`
+//!
+//! one vector per RFC5054 group, all with the inputs of RFC5054 Appendix B,
+//! numbers are upper case hex in big endian, the hashes are taken over little endian bytes
+use serde::Serialize;
+use srp6::testing::{conformance_report, ConformanceInputs, ConformanceReport};
+use srp6::*;
+
+#[derive(Serialize)]
+struct Fixture {
+    hash: &'static str,
+    hash_byte_order: &'static str,
+    vectors: Vec<Vector>,
+}
+
+#[derive(Serialize)]
+struct Vector {
+    group: &'static str,
+    inputs: Inputs,
+    outputs: ConformanceReport,
+}
+
+#[derive(Serialize)]
+struct Inputs {
+    username: String,
+    password: String,
+    salt: String,
+    a: String,
+    b: String,
+    #[serde(rename = "N")]
+    module: String,
+    g: String,
+}
+
+fn vector<const KEY_LENGTH: usize>(group: &'static str) -> Vector {
+    let inputs = ConformanceInputs {
+        constants: group_by_name(group).expect("the group is enabled"),
+        ..ConformanceInputs::rfc5054_appendix_b()
+    };
+    let outputs = conformance_report::<KEY_LENGTH, 16>(&inputs).expect("valid inputs");
+
+    Vector {
+        group,
+        inputs: Inputs {
+            username: inputs.username,
+            password: inputs.password,
+            salt: inputs.salt.to_string(),
+            a: inputs.a.to_string(),
+            b: inputs.b.to_string(),
+            module: inputs.constants.module.to_string(),
+            g: inputs.constants.generator.to_string(),
+        },
+        outputs,
+    }
+}
+
+fn main() {
+    let fixture = Fixture {
+        hash: "SHA1",
+        hash_byte_order: "little endian",
+        vectors: vec![
+            vector::<128>("rfc5054-1024"),
+            vector::<192>("rfc5054-1536"),
+            vector::<256>("rfc5054-2048"),
+            vector::<384>("rfc5054-3072"),
+            vector::<512>("rfc5054-4096"),
+        ],
+    };
+    let json = serde_json::to_string_pretty(&fixture).expect("the fixture is serializable");
+
+    match std::env::args().nth(1) {
+        Some(path) => {
+            std::fs::write(&path, json).expect("the fixture file is writable");
+            println!("Wrote {} vectors to {}", fixture.vectors.len(), path);
+        }
+        None => println!("{}", json),
+    }
+}
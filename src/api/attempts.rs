@@ -0,0 +1,139 @@
+/*!
+Limits online password guessing by locking a username after too many failed proofs.
+
+The failed attempts are kept by an [`AttemptTracker`], the application implements it
+over its own store (e.g. a database or a cache with expiry), for tests and single
+process servers there is [`InMemoryAttemptTracker`].
+
+[`Srp6::verify_proof_rate_limited`] refuses to check a proof once the limit is reached,
+counts every invalid proof and resets the count after a successful one.
+
+## Unlocking
+The tracker decides when a username is unlocked again, e.g. by letting the counts
+expire after some minutes, [`AttemptTracker::reset`] unlocks right away.
+*/
+use std::collections::HashMap;
+
+use crate::primitives::{Proof, StrongProof, UsernameRef};
+use crate::{HostAPI, Result, Srp6, Srp6Error};
+
+/// counts failed proofs per username, implemented by the application over its own store
+pub trait AttemptTracker {
+    /// failed attempts at which the username is locked
+    fn max_failed_attempts(&self) -> u32;
+
+    /// failed attempts counted so far for `username`
+    fn failed_attempts(&self, username: UsernameRef) -> u32;
+
+    /// counts one failed attempt for `username`
+    fn record_failure(&mut self, username: UsernameRef);
+
+    /// forgets the failed attempts of `username`, e.g. after a successful proof
+    fn reset(&mut self, username: UsernameRef);
+}
+
+/// [`AttemptTracker`] that keeps the counts in memory, they never expire
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryAttemptTracker {
+    max_failed_attempts: u32,
+    failed_attempts: HashMap<String, u32>,
+}
+
+impl InMemoryAttemptTracker {
+    pub fn new(max_failed_attempts: u32) -> Self {
+        Self {
+            max_failed_attempts,
+            failed_attempts: HashMap::new(),
+        }
+    }
+}
+
+impl AttemptTracker for InMemoryAttemptTracker {
+    fn max_failed_attempts(&self) -> u32 {
+        self.max_failed_attempts
+    }
+
+    fn failed_attempts(&self, username: UsernameRef) -> u32 {
+        self.failed_attempts.get(username).copied().unwrap_or(0)
+    }
+
+    fn record_failure(&mut self, username: UsernameRef) {
+        *self.failed_attempts.entry(username.to_owned()).or_insert(0) += 1;
+    }
+
+    fn reset(&mut self, username: UsernameRef) {
+        self.failed_attempts.remove(username);
+    }
+}
+
+impl<const KEY_LENGTH: usize, const SALT_LENGTH: usize> Srp6<KEY_LENGTH, SALT_LENGTH> {
+    /// same as [`HostAPI::verify_proof`], but fails with [`Srp6Error::TooManyAttempts`]
+    /// without checking the proof once the user of the handshake, see [`Srp6::username`],
+    /// reached the limit of the `tracker`
+    ///
+    /// an invalid proof is counted, a valid one resets the count
+    pub fn verify_proof_rate_limited<T: AttemptTracker>(
+        &mut self,
+        users_proof: &Proof,
+        tracker: &mut T,
+    ) -> Result<StrongProof> {
+        let username = self.username().to_owned();
+        if tracker.failed_attempts(&username) >= tracker.max_failed_attempts() {
+            return Err(Srp6Error::TooManyAttempts);
+        }
+
+        match self.verify_proof(users_proof) {
+            Ok(strong_proof) => {
+                tracker.reset(&username);
+                Ok(strong_proof)
+            }
+            Err(e @ Srp6Error::InvalidProof(_)) => {
+                tracker.record_failure(&username);
+                Err(e)
+            }
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "group-1024")]
+mod tests {
+    use super::*;
+    use crate::api::new_user::tests::handshake;
+    use crate::Srp6User;
+
+    type Srp6_1024 = Srp6<128, 128>;
+    type Srp6user1024 = Srp6User<128, 128>;
+
+    #[test]
+    fn should_lock_the_username_after_too_many_failed_attempts() {
+        let mut tracker = InMemoryAttemptTracker::new(2);
+        let (mut host, mut user) = (Srp6_1024::new(), Srp6user1024::new());
+        let proof = handshake(&mut host, &mut user);
+
+        // counted for the user of the handshake
+        assert_eq!(host.username(), "Bob");
+        // a valid proof resets the count
+        host.verify_proof_rate_limited(&Proof::from(7), &mut tracker)
+            .unwrap_err();
+        assert_eq!(tracker.failed_attempts("Bob"), 1);
+        assert!(host.verify_proof_rate_limited(&proof, &mut tracker).is_ok());
+        assert_eq!(tracker.failed_attempts("Bob"), 0);
+
+        for _ in 0..2 {
+            assert!(matches!(
+                host.verify_proof_rate_limited(&Proof::from(7), &mut tracker),
+                Err(Srp6Error::InvalidProof(_))
+            ));
+        }
+        assert_eq!(
+            host.verify_proof_rate_limited(&proof, &mut tracker),
+            Err(Srp6Error::TooManyAttempts)
+        );
+        assert_eq!(tracker.failed_attempts("Alice"), 0);
+
+        tracker.reset("Bob");
+        assert!(host.verify_proof_rate_limited(&proof, &mut tracker).is_ok());
+    }
+}
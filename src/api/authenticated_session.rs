@@ -0,0 +1,130 @@
+/*!
+The one object an application holds on to after the host verified the client.
+
+[`Srp6::verify_proof_authenticated`] verifies the proof like [`HostAPI::verify_proof`]
+and bundles the result as an [`AuthenticatedSession`]: the username, the strong proof
+`M2` to send back, the [`StrongSessionKey`] `K` and keys derived from it.
+An existing [`VerifiedSession`] is converted with [`VerifiedSession::into_authenticated`].
+*/
+use std::fmt::{Debug, Formatter};
+
+use serde::Serialize;
+
+use crate::hash::HASH_LENGTH;
+use crate::primitives::*;
+use crate::{GroupInfo, HostAPI, Result, Srp6, Srp6Error};
+
+/// HKDF can expand at most 255 blocks of the hash
+const MAX_DERIVED_KEY_LENGTH: usize = 255 * HASH_LENGTH;
+
+/// a session after the host verified the client, see [`Srp6::verify_proof_authenticated`]
+#[derive(Clone, Serialize)]
+pub struct AuthenticatedSession {
+    /// the user who proved to know the password
+    pub username: Username,
+    /// `M2`, to be sent to the client
+    pub strong_proof: StrongProof,
+    /// `K`, shared with the client
+    pub session_key: StrongSessionKey,
+    /// `A` exactly as used in the handshake, e.g. for audit logs or channel bindings
+    pub client_public_key: PublicKey,
+    /// the group of the handshake, e.g. to log the strength of the authentication
+    pub group: GroupInfo,
+}
+
+impl AuthenticatedSession {
+    /// derives a key of `len` bytes from `K`, `info` binds it to its purpose, e.g. `b"my-app enc"`
+    ///
+    /// formula: `HKDF-Expand(HKDF-Extract(0, PAD(K)), info)`,
+    /// fails with [`Srp6Error::KeyLengthMismatch`] for more than `255 * HASH_LENGTH` bytes
+    pub fn derive_key(&self, info: &[u8], len: usize) -> Result<Vec<u8>> {
+        if len > MAX_DERIVED_KEY_LENGTH {
+            return Err(Srp6Error::KeyLengthMismatch {
+                given: len,
+                expected: MAX_DERIVED_KEY_LENGTH,
+            });
+        }
+        let mut key = vec![0_u8; len];
+        self.session_key.derive_into(&[info], &mut key);
+
+        Ok(key)
+    }
+}
+
+impl Debug for AuthenticatedSession {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("AuthenticatedSession")
+            .field("username", &self.username)
+            .field("strong_proof", &self.strong_proof)
+            .field("session_key", &Redacted("StrongSessionKey"))
+            .field("client_public_key", &self.client_public_key)
+            .field("group", &self.group)
+            .finish()
+    }
+}
+
+impl VerifiedSession {
+    /// this session as an [`AuthenticatedSession`] of the user of the handshake
+    pub fn into_authenticated(self) -> AuthenticatedSession {
+        AuthenticatedSession {
+            username: self.username,
+            strong_proof: self.strong_proof,
+            session_key: self.session_key,
+            client_public_key: self.client_public_key,
+            group: self.group,
+        }
+    }
+}
+
+impl<const KEY_LENGTH: usize, const SALT_LENGTH: usize> Srp6<KEY_LENGTH, SALT_LENGTH> {
+    /// same as [`HostAPI::verify_proof_with_session`], but returns an [`AuthenticatedSession`]
+    /// of the user the handshake was continued for
+    pub fn verify_proof_authenticated(
+        &mut self,
+        users_proof: &Proof,
+    ) -> Result<AuthenticatedSession> {
+        Ok(self
+            .verify_proof_with_session(users_proof)?
+            .into_authenticated())
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "group-1024")]
+mod tests {
+    use super::*;
+    use crate::api::new_user::tests::handshake;
+    use crate::{Srp6User, UserTrait};
+
+    type Srp6_1024 = Srp6<128, 128>;
+    type Srp6user1024 = Srp6User<128, 128>;
+
+    #[test]
+    fn should_bundle_the_authenticated_session() {
+        let (mut host, mut user) = (Srp6_1024::new(), Srp6user1024::new());
+        let proof = handshake(&mut host, &mut user);
+
+        assert!(matches!(
+            host.verify_proof_authenticated(&Proof::from(7)),
+            Err(Srp6Error::InvalidProof(_))
+        ));
+        let session = host.verify_proof_authenticated(&proof).unwrap();
+        assert_eq!(session.username, "Bob");
+        assert_eq!(session.client_public_key, user.A);
+        let (_, session_key) = user.verify_proof(&session.strong_proof).unwrap();
+        assert_eq!(session.session_key, session_key);
+        assert!(!format!("{:?}", session).contains(&session_key.to_string()));
+
+        let key = session.derive_key(b"my app", 32).unwrap();
+        assert_eq!(key.len(), 32);
+        assert_eq!(key, session.derive_key(b"my app", 32).unwrap());
+        assert_ne!(key, session.derive_key(b"other app", 32).unwrap());
+        assert_eq!(
+            session.derive_key(b"my app", 255 * HASH_LENGTH + 1),
+            Err(Srp6Error::KeyLengthMismatch {
+                given: 255 * HASH_LENGTH + 1,
+                expected: 255 * HASH_LENGTH
+            })
+        );
+    }
+}
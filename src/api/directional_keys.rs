@@ -0,0 +1,146 @@
+/*!
+Separate MAC keys per direction, derived from the [`StrongSessionKey`] `K`.
+
+Using `K` itself, or one key for both directions, allows to reflect a message
+of one side back to it. [`derive_directional_keys`] derives two independent keys:
+- client to server: `HKDF(K, info | "c2s")`
+- server to client: `HKDF(K, info | "s2c")`
+
+Both are [`HASH_LENGTH`] bytes long and meant as `HMAC` keys.
+
+For an AEAD channel [`StrongSessionKey::nonce_for`] derives the 96 bit nonce of a message
+from `K`, the [`Direction`] and a message counter, as in TLS 1.3:
+- nonce base: `HKDF-Expand(HKDF-Extract("srp6 nonce", PAD(K)), label)`, 12 bytes,
+  the salt `"srp6 nonce"` separates it from the keys, whatever their `info`
+- nonce: `nonce base xor PAD(counter)`, the counter in little endian
+*/
+use hkdf::Hkdf;
+
+use crate::hash::{HashFunc, HASH_LENGTH};
+use crate::primitives::{StrongSessionKey, STRONG_SESSION_KEY_LENGTH};
+
+const CLIENT_TO_SERVER_LABEL: &[u8] = b"c2s";
+const SERVER_TO_CLIENT_LABEL: &[u8] = b"s2c";
+/// the HKDF salt of the nonces, the keys are extracted without a salt
+const NONCE_SALT: &[u8] = b"srp6 nonce";
+/// the nonce length of AEADs like AES-GCM and ChaCha20-Poly1305
+const NONCE_LENGTH: usize = 12;
+
+/// the direction of a message, so both sides never derive the same nonce
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Direction {
+    ClientToServer,
+    ServerToClient,
+}
+
+impl Direction {
+    fn label(self) -> &'static [u8] {
+        match self {
+            Direction::ClientToServer => CLIENT_TO_SERVER_LABEL,
+            Direction::ServerToClient => SERVER_TO_CLIENT_LABEL,
+        }
+    }
+}
+
+/// derives the client to server and the server to client key from `K`,
+/// `info` binds the keys to an application or protocol, e.g. `b"my-app v1"`
+///
+/// formula: `HKDF-Expand(HKDF-Extract(0, PAD(K)), info | label)` with the labels `"c2s"` and `"s2c"`
+#[allow(non_snake_case)]
+pub fn derive_directional_keys(K: &StrongSessionKey, info: &[u8]) -> (Vec<u8>, Vec<u8>) {
+    let expand = |label: &[u8]| {
+        let mut key = vec![0_u8; HASH_LENGTH];
+        K.derive_into(&[info, label], &mut key);
+        key
+    };
+
+    (
+        expand(Direction::ClientToServer.label()),
+        expand(Direction::ServerToClient.label()),
+    )
+}
+
+impl StrongSessionKey {
+    /// the 96 bit AEAD nonce of the message number `counter` in `direction`,
+    /// distinct for every counter and direction of this `K`
+    ///
+    /// **NOTE**: each side counts its messages from 0 and must never reuse a counter,
+    /// e.g. after a wrap around, a new handshake is required instead
+    pub fn nonce_for(&self, counter: u64, direction: Direction) -> [u8; NONCE_LENGTH] {
+        let hkdf = Hkdf::<HashFunc>::new(
+            Some(NONCE_SALT),
+            &self.to_array_pad_zero::<STRONG_SESSION_KEY_LENGTH>(),
+        );
+        let mut nonce = [0_u8; NONCE_LENGTH];
+        hkdf.expand(direction.label(), &mut nonce)
+            .expect("NONCE_LENGTH is a valid length for HKDF");
+        for (n, c) in nonce.iter_mut().zip(counter.to_le_bytes().iter()) {
+            *n ^= c;
+        }
+
+        nonce
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_derive_the_directional_keys() {
+        let (c2s, s2c) = derive_directional_keys(&StrongSessionKey::from(1), b"my app");
+
+        assert_eq!(hex::encode(c2s), "88ffe4da0198abea5c585983209261b463bb36be");
+        assert_eq!(hex::encode(s2c), "efcc56f4d2541bd7c7d73a33993037a0abf5102c");
+    }
+
+    #[test]
+    fn should_bind_the_keys_to_the_info() {
+        let K = StrongSessionKey::from(42);
+        let (c2s, s2c) = derive_directional_keys(&K, b"my app");
+        let (other_c2s, _) = derive_directional_keys(&K, b"other app");
+
+        assert_ne!(c2s, s2c);
+        assert_ne!(c2s, other_c2s);
+        assert_eq!((c2s, s2c), derive_directional_keys(&K, b"my app"));
+    }
+
+    #[test]
+    fn should_derive_distinct_nonces_per_counter_and_direction() {
+        let K = StrongSessionKey::from(42);
+        let mut nonces = std::collections::HashSet::new();
+        for counter in [0, 1, 2, u64::MAX].iter().copied() {
+            for direction in [Direction::ClientToServer, Direction::ServerToClient]
+                .iter()
+                .copied()
+            {
+                let nonce = K.nonce_for(counter, direction);
+                assert_eq!(nonce, K.nonce_for(counter, direction));
+                assert!(nonces.insert(nonce));
+            }
+        }
+
+        assert_ne!(
+            K.nonce_for(0, Direction::ClientToServer),
+            StrongSessionKey::from(43).nonce_for(0, Direction::ClientToServer)
+        );
+    }
+
+    #[test]
+    fn should_not_derive_a_nonce_base_as_key() {
+        let K = StrongSessionKey::from(42);
+
+        for direction in [Direction::ClientToServer, Direction::ServerToClient]
+            .iter()
+            .copied()
+        {
+            let nonce_base = K.nonce_for(0, direction);
+            for info in [&b"srp6 nonce"[..], b""].iter() {
+                let (c2s, s2c) = derive_directional_keys(&K, info);
+                assert_ne!(c2s[..NONCE_LENGTH], nonce_base);
+                assert_ne!(s2c[..NONCE_LENGTH], nonce_base);
+            }
+        }
+    }
+}
@@ -0,0 +1,175 @@
+/*!
+Hides whether a username exists from the timing and the answers of the host.
+
+A host that answers unknown usernames with an error, or faster, lets an attacker
+enumerate its users. [`Srp6::user_details_or_dummy`] instead returns [`UserDetails`]
+for every username, a dummy for unknown ones, and the handshake continues as usual.
+The client's proof simply fails at the end, just like for a wrong password.
+
+The dummy is derived from a server secret, so the same unknown username always gets
+the same salt, otherwise an attacker could tell it apart by asking twice:
+- salt: `HKDF(secret, I | "salt")`
+- verifier: `HKDF(secret, I | "verifier") % N`
+
+[`Srp6::fake_user_details`] returns just the dummy, e.g. for a store that looks up users itself.
+
+Both are calculated for every username and the result is selected in constant time,
+so the work done is the same for known and unknown users, except for the `lookup`
+which is up to the application.
+*/
+use hkdf::Hkdf;
+use subtle::{Choice, ConditionallySelectable};
+
+use crate::hash::HashFunc;
+use crate::primitives::*;
+use crate::Srp6;
+
+const DUMMY_SALT_LABEL: &[u8] = b"salt";
+const DUMMY_VERIFIER_LABEL: &[u8] = b"verifier";
+
+impl<const KEY_LENGTH: usize, const SALT_LENGTH: usize> Srp6<KEY_LENGTH, SALT_LENGTH> {
+    /// the [`UserDetails`] of `I` from `lookup`, or a deterministic dummy if `I` is unknown,
+    /// `server_secret` must be random, kept secret and stay the same across restarts
+    ///
+    /// continue with [`crate::HostAPI::continue_handshake`] in both cases
+    #[allow(non_snake_case)]
+    pub fn user_details_or_dummy<F>(
+        I: UsernameRef,
+        lookup: F,
+        server_secret: &[u8],
+        constants: &OpenConstants,
+    ) -> UserDetails
+    where
+        F: Fn(UsernameRef) -> Option<(Salt, PasswordVerifier)>,
+    {
+        let (dummy_salt, dummy_verifier) = Self::derive_dummy(I, server_secret);
+
+        let found = lookup(I);
+        let is_known = Choice::from(found.is_some() as u8);
+        let (salt, verifier) = found.unwrap_or_default();
+        let salt = select(
+            &dummy_salt,
+            &salt.to_array_pad_zero::<SALT_LENGTH>(),
+            is_known,
+        );
+        let verifier = select(
+            &dummy_verifier,
+            &verifier.to_array_pad_zero::<KEY_LENGTH>(),
+            is_known,
+        );
+
+        UserDetails {
+            username: I.to_owned(),
+            salt: Salt::from(salt),
+            verifier: &PasswordVerifier::from(verifier) % &constants.module,
+        }
+    }
+
+    /// the deterministic dummy [`UserDetails`] of `I`, the same for every call with the same
+    /// `server_secret`, as used by [`Srp6::user_details_or_dummy`] for unknown users
+    ///
+    /// only the salt and verifier are stable, `b` and so `B` stay random per handshake
+    #[allow(non_snake_case)]
+    pub fn fake_user_details(
+        I: UsernameRef,
+        server_secret: &[u8],
+        constants: &OpenConstants,
+    ) -> UserDetails {
+        let (salt, verifier) = Self::derive_dummy(I, server_secret);
+
+        UserDetails {
+            username: I.to_owned(),
+            salt: Salt::from(salt),
+            verifier: &PasswordVerifier::from(verifier) % &constants.module,
+        }
+    }
+
+    /// the dummy salt and verifier of `I`, the verifier is not reduced modulo `N` yet
+    #[allow(non_snake_case)]
+    fn derive_dummy(I: UsernameRef, server_secret: &[u8]) -> ([u8; SALT_LENGTH], [u8; KEY_LENGTH]) {
+        let hkdf = Hkdf::<HashFunc>::new(Some(server_secret), I.as_bytes());
+        let mut salt = [0_u8; SALT_LENGTH];
+        hkdf.expand(DUMMY_SALT_LABEL, &mut salt)
+            .expect("SALT_LENGTH is a valid length for HKDF");
+        let mut verifier = [0_u8; KEY_LENGTH];
+        hkdf.expand(DUMMY_VERIFIER_LABEL, &mut verifier)
+            .expect("KEY_LENGTH is a valid length for HKDF");
+
+        (salt, verifier)
+    }
+}
+
+/// `known` if `is_known` is set, otherwise `dummy`, without branching on `is_known`
+fn select<const N: usize>(dummy: &[u8; N], known: &[u8; N], is_known: Choice) -> [u8; N] {
+    let mut selected = [0_u8; N];
+    for (s, (d, k)) in selected.iter_mut().zip(dummy.iter().zip(known.iter())) {
+        *s = u8::conditional_select(d, k, is_known);
+    }
+
+    selected
+}
+
+#[cfg(test)]
+#[cfg(feature = "group-1024")]
+mod tests {
+    use super::*;
+    use crate::{rfc5054_1024, HostAPI, Srp6Error, Srp6User, UserTrait};
+
+    type Srp6_1024 = Srp6<128, 128>;
+
+    const SERVER_SECRET: &[u8] = b"a random server secret";
+
+    #[test]
+    fn should_derive_the_same_fake_user_details_per_username() {
+        let constants = rfc5054_1024();
+        let fake = Srp6_1024::fake_user_details("Eve", SERVER_SECRET, &constants);
+        let again = Srp6_1024::fake_user_details("Eve", SERVER_SECRET, &constants);
+        assert_eq!(fake.username, "Eve");
+        assert_eq!((&fake.salt, &fake.verifier), (&again.salt, &again.verifier));
+        assert!(Srp6_1024::validate_user(&fake).is_ok());
+
+        let other_user = Srp6_1024::fake_user_details("Mallory", SERVER_SECRET, &constants);
+        let other_secret = Srp6_1024::fake_user_details("Eve", b"another secret", &constants);
+        assert_ne!(fake.verifier, other_user.verifier);
+        assert_ne!(fake.salt, other_secret.salt);
+    }
+
+    #[test]
+    fn should_answer_unknown_users_with_a_stable_dummy() {
+        let constants = rfc5054_1024();
+        let bob = Srp6_1024::new().generate_new_user_secrets("Bob", "secret-password", &constants);
+        let lookup = |username: UsernameRef| {
+            (username == "Bob").then(|| (bob.salt.clone(), bob.verifier.clone()))
+        };
+
+        let known = Srp6_1024::user_details_or_dummy("Bob", lookup, SERVER_SECRET, &constants);
+        assert_eq!(known.salt, bob.salt);
+        assert_eq!(known.verifier, bob.verifier);
+
+        let dummy = Srp6_1024::user_details_or_dummy("Eve", lookup, SERVER_SECRET, &constants);
+        let again = Srp6_1024::user_details_or_dummy("Eve", lookup, SERVER_SECRET, &constants);
+        assert_eq!(dummy.salt, again.salt);
+        assert_eq!(dummy.verifier, again.verifier);
+        assert!(Srp6_1024::validate_user(&dummy).is_ok());
+        let other = Srp6_1024::user_details_or_dummy("Mallory", lookup, SERVER_SECRET, &constants);
+        assert_ne!(dummy.salt, other.salt);
+        let fake = Srp6_1024::fake_user_details("Eve", SERVER_SECRET, &constants);
+        assert_eq!(fake.salt, dummy.salt);
+        assert_eq!(fake.verifier, dummy.verifier);
+
+        // the handshake runs as for a known user and fails at the proof
+        let mut host = Srp6_1024::new();
+        let mut user = Srp6User::<128, 128>::new();
+        let user_handshake = user.start_handshake("Eve", &constants);
+        let server_handshake = host
+            .continue_handshake(&dummy, &user_handshake, &constants)
+            .unwrap();
+        let proof = user
+            .update_handshake(&server_handshake, &constants, "Eve", "guess")
+            .unwrap();
+        assert!(matches!(
+            host.verify_proof(&proof),
+            Err(Srp6Error::InvalidProof(_))
+        ));
+    }
+}
@@ -0,0 +1,395 @@
+/*!
+Runtime selection of a group by its name, e.g. from a config file.
+
+[`Srp6`] carries the key length as const generic, so [`Srp6Dyn`] dispatches
+to the matching [`Srp6`] for each of the enabled [RFC5054] groups:
+`"rfc5054-1024"`, `"rfc5054-1536"`, `"rfc5054-2048"`, `"rfc5054-3072"` and `"rfc5054-4096"`.
+
+[RFC5054]: https://datatracker.ietf.org/doc/html/rfc5054#appendix-A
+*/
+use crate::primitives::*;
+use crate::{GroupId, GroupInfo, HostAPI, Result, Srp6, Srp6Error, Srp6User, UserTrait};
+
+/// the [RFC5054] group for a name like `"rfc5054-2048"`,
+/// fails with [`Srp6Error::UnknownGroup`] for unknown or not enabled groups
+///
+/// [RFC5054]: https://datatracker.ietf.org/doc/html/rfc5054#appendix-A
+pub fn group_by_name(name: &str) -> Result<OpenConstants> {
+    match name {
+        #[cfg(feature = "group-1024")]
+        "rfc5054-1024" => Ok(crate::rfc5054_1024()),
+        #[cfg(feature = "group-1536")]
+        "rfc5054-1536" => Ok(crate::rfc5054_1536()),
+        #[cfg(feature = "group-2048")]
+        "rfc5054-2048" => Ok(crate::rfc5054_2048()),
+        #[cfg(feature = "group-3072")]
+        "rfc5054-3072" => Ok(crate::rfc5054_3072()),
+        #[cfg(feature = "group-4096")]
+        "rfc5054-4096" => Ok(crate::rfc5054_4096()),
+        _ => Err(Srp6Error::UnknownGroup(name.to_owned())),
+    }
+}
+
+/// the enabled [RFC5054] group a stored verifier of `len` bytes belongs to,
+/// e.g. to dispatch each user to the [`Srp6`] of their group while migrating a database
+///
+/// **NOTE**: the length identifies the group only among the [RFC5054] groups, which all
+/// differ in length, and only if verifiers are stored padded to the length of `N`
+/// (`PAD(v)`), as `v` can have leading zero bytes
+///
+/// [RFC5054]: https://datatracker.ietf.org/doc/html/rfc5054#appendix-A
+pub fn group_for_verifier_len(len: usize) -> Option<GroupInfo> {
+    GROUP_NAMES
+        .iter()
+        .filter_map(|name| group_by_name(name).ok())
+        .find(|constants| constants.module.byte_len() == len)
+        .map(|constants| constants.group_info())
+}
+
+const GROUP_NAMES: [&str; 5] = [
+    "rfc5054-1024",
+    "rfc5054-1536",
+    "rfc5054-2048",
+    "rfc5054-3072",
+    "rfc5054-4096",
+];
+
+impl OpenConstants {
+    /// the modulus `N` and generator `g`, e.g. to check them against a list of accepted groups
+    pub fn group_params(&self) -> (&PrimeModulus, &Generator) {
+        (&self.module, &self.generator)
+    }
+
+    /// the name of the enabled [RFC5054] group with the same `N` and `g`, see [`group_by_name`],
+    /// or `None` for a custom group, a client should refuse groups it does not know
+    ///
+    /// [RFC5054]: https://datatracker.ietf.org/doc/html/rfc5054#appendix-A
+    pub fn matches_known_group(&self) -> Option<&'static str> {
+        known_group_name(&self.group_id())
+    }
+}
+
+impl GroupInfo {
+    /// the name of the enabled [RFC5054] group, see [`OpenConstants::matches_known_group`]
+    ///
+    /// [RFC5054]: https://datatracker.ietf.org/doc/html/rfc5054#appendix-A
+    pub fn name(&self) -> Option<&'static str> {
+        known_group_name(&self.id)
+    }
+}
+
+fn known_group_name(id: &GroupId) -> Option<&'static str> {
+    GROUP_NAMES.iter().copied().find(|name| {
+        group_by_name(name)
+            .map(|constants| &constants.group_id() == id)
+            .unwrap_or(false)
+    })
+}
+
+macro_rules! dispatch {
+    ($any:ident, $value:expr, $srp:ident => $call:expr) => {
+        match $value {
+            $any::B1024($srp) => $call,
+            $any::B1536($srp) => $call,
+            $any::B2048($srp) => $call,
+            $any::B3072($srp) => $call,
+            $any::B4096($srp) => $call,
+        }
+    };
+}
+
+macro_rules! from_key_length {
+    ($any:ident, $key_length:expr, $new:expr) => {
+        match $key_length {
+            128 => Ok($any::B1024($new)),
+            192 => Ok($any::B1536($new)),
+            256 => Ok($any::B2048($new)),
+            384 => Ok($any::B3072($new)),
+            512 => Ok($any::B4096($new)),
+            given => Err(Srp6Error::KeyLengthMismatch {
+                given,
+                expected: 512,
+            }),
+        }
+    };
+}
+
+/// [`Srp6`] for any of the [RFC5054] key lengths, e.g. to keep the host of a
+/// connection in one variable when the group is negotiated per connection
+///
+/// [`UserHandshake`], [`ServerHandshake`] and [`Proof`] do not depend on the key length,
+/// so they are shared with [`Srp6`].
+///
+/// [RFC5054]: https://datatracker.ietf.org/doc/html/rfc5054#appendix-A
+#[derive(Debug)]
+pub enum AnySrp6 {
+    B1024(Srp6<128, 128>),
+    B1536(Srp6<192, 192>),
+    B2048(Srp6<256, 256>),
+    B3072(Srp6<384, 384>),
+    B4096(Srp6<512, 512>),
+}
+
+impl AnySrp6 {
+    /// the [`Srp6`] matching the key length of `constants`,
+    /// fails with [`Srp6Error::KeyLengthMismatch`] for other key lengths
+    pub fn for_group(constants: &OpenConstants) -> Result<Self> {
+        from_key_length!(AnySrp6, constants.module.byte_len(), Srp6::new())
+    }
+
+    /// same as [`Srp6::KEY_LEN`]
+    pub fn key_length(&self) -> usize {
+        match self {
+            AnySrp6::B1024(_) => 128,
+            AnySrp6::B1536(_) => 192,
+            AnySrp6::B2048(_) => 256,
+            AnySrp6::B3072(_) => 384,
+            AnySrp6::B4096(_) => 512,
+        }
+    }
+
+    /// see [`HostAPI::generate_new_user_secrets`]
+    #[allow(non_snake_case)]
+    pub fn generate_new_user_secrets(
+        &mut self,
+        I: UsernameRef,
+        p: &ClearTextPassword,
+        constants: &OpenConstants,
+    ) -> UserDetails {
+        dispatch!(AnySrp6, self, srp => srp.generate_new_user_secrets(I, p, constants))
+    }
+
+    /// see [`HostAPI::continue_handshake`]
+    pub fn continue_handshake(
+        &mut self,
+        user_details: &UserDetails,
+        user_handshake: &UserHandshake,
+        constants: &OpenConstants,
+    ) -> Result<ServerHandshake> {
+        dispatch!(AnySrp6, self, srp => srp.continue_handshake(user_details, user_handshake, constants))
+    }
+
+    /// see [`HostAPI::verify_proof`]
+    pub fn verify_proof(&mut self, users_proof: &Proof) -> Result<StrongProof> {
+        dispatch!(AnySrp6, self, srp => srp.verify_proof(users_proof))
+    }
+
+    /// see [`Srp6::session_key`]
+    pub fn session_key(&self) -> Option<&StrongSessionKey> {
+        dispatch!(AnySrp6, self, srp => srp.session_key())
+    }
+}
+
+/// [`Srp6User`] for any of the [RFC5054] key lengths, the client side of [`AnySrp6`]
+///
+/// [RFC5054]: https://datatracker.ietf.org/doc/html/rfc5054#appendix-A
+#[derive(Debug)]
+pub enum AnySrp6User {
+    B1024(Srp6User<128, 128>),
+    B1536(Srp6User<192, 192>),
+    B2048(Srp6User<256, 256>),
+    B3072(Srp6User<384, 384>),
+    B4096(Srp6User<512, 512>),
+}
+
+impl AnySrp6User {
+    /// the [`Srp6User`] matching the key length of `constants`,
+    /// fails with [`Srp6Error::KeyLengthMismatch`] for other key lengths
+    pub fn for_group(constants: &OpenConstants) -> Result<Self> {
+        from_key_length!(AnySrp6User, constants.module.byte_len(), Srp6User::new())
+    }
+
+    /// see [`UserTrait::start_handshake`]
+    pub fn start_handshake(
+        &mut self,
+        username: UsernameRef,
+        constants: &OpenConstants,
+    ) -> UserHandshake {
+        dispatch!(AnySrp6User, self, srp => srp.start_handshake(username, constants))
+    }
+
+    /// see [`UserTrait::update_handshake`]
+    #[allow(non_snake_case)]
+    pub fn update_handshake(
+        &mut self,
+        server_handshake: &ServerHandshake,
+        constants: &OpenConstants,
+        I: UsernameRef,
+        p: &ClearTextPassword,
+    ) -> Result<Proof> {
+        dispatch!(AnySrp6User, self, srp => srp.update_handshake(server_handshake, constants, I, p))
+    }
+
+    /// see [`UserTrait::verify_proof`]
+    pub fn verify_proof(
+        &mut self,
+        servers_proof: &StrongProof,
+    ) -> Result<(StrongProof, StrongSessionKey)> {
+        dispatch!(AnySrp6User, self, srp => srp.verify_proof(servers_proof))
+    }
+}
+
+/// Server side like [`Srp6`], with the group chosen at runtime by name
+#[derive(Debug)]
+pub struct Srp6Dyn {
+    constants: OpenConstants,
+    host: AnySrp6,
+}
+
+impl Srp6Dyn {
+    /// see [`group_by_name`] for the names
+    pub fn from_group_name(name: &str) -> Result<Self> {
+        let constants = group_by_name(name)?;
+        let host = AnySrp6::for_group(&constants)?;
+
+        Ok(Self { constants, host })
+    }
+
+    /// the group this host was created for
+    pub fn constants(&self) -> &OpenConstants {
+        &self.constants
+    }
+
+    /// the key length of the group in bytes, same as [`Srp6::KEY_LEN`]
+    pub fn key_length(&self) -> usize {
+        self.host.key_length()
+    }
+
+    /// see [`HostAPI::generate_new_user_secrets`]
+    #[allow(non_snake_case)]
+    pub fn generate_new_user_secrets(
+        &mut self,
+        I: UsernameRef,
+        p: &ClearTextPassword,
+    ) -> UserDetails {
+        self.host.generate_new_user_secrets(I, p, &self.constants)
+    }
+
+    /// see [`HostAPI::continue_handshake`]
+    pub fn continue_handshake(
+        &mut self,
+        user_details: &UserDetails,
+        user_handshake: &UserHandshake,
+    ) -> Result<ServerHandshake> {
+        self.host
+            .continue_handshake(user_details, user_handshake, &self.constants)
+    }
+
+    /// see [`HostAPI::verify_proof`]
+    pub fn verify_proof(&mut self, users_proof: &Proof) -> Result<StrongProof> {
+        self.host.verify_proof(users_proof)
+    }
+
+    /// see [`Srp6::session_key`]
+    pub fn session_key(&self) -> Option<&StrongSessionKey> {
+        self.host.session_key()
+    }
+}
+
+#[cfg(test)]
+#[cfg(feature = "group-1024")]
+mod tests {
+    use super::*;
+    use crate::rfc5054_1024;
+
+    #[test]
+    fn should_resolve_the_group_names() {
+        let host = Srp6Dyn::from_group_name("rfc5054-1024").unwrap();
+        assert_eq!(host.key_length(), 128);
+        assert_eq!(host.constants().group_id(), rfc5054_1024().group_id());
+
+        assert_eq!(
+            Srp6Dyn::from_group_name("rfc5054-1000").err(),
+            Some(Srp6Error::UnknownGroup("rfc5054-1000".to_owned()))
+        );
+    }
+
+    #[test]
+    fn should_name_known_groups() {
+        let constants = rfc5054_1024();
+        assert_eq!(constants.matches_known_group(), Some("rfc5054-1024"));
+        assert_eq!(
+            constants.group_params(),
+            (&constants.module, &constants.generator)
+        );
+
+        let custom = OpenConstants {
+            module: constants.module.clone(),
+            generator: Generator::from(5),
+        };
+        assert_eq!(custom.matches_known_group(), None);
+    }
+
+    #[test]
+    fn should_authenticate_with_a_group_chosen_at_runtime() {
+        let mut host = Srp6Dyn::from_group_name("rfc5054-1024").unwrap();
+        let constants = host.constants().clone();
+        let details = host.generate_new_user_secrets("Bob", "secret-password");
+
+        let mut user = Srp6User::<128, 128>::new();
+        let user_handshake = user.start_handshake("Bob", &constants);
+        let server_handshake = host.continue_handshake(&details, &user_handshake).unwrap();
+        let proof = user
+            .update_handshake(&server_handshake, &constants, "Bob", "secret-password")
+            .unwrap();
+
+        let strong_proof = host.verify_proof(&proof).unwrap();
+        assert!(user.verify_proof(&strong_proof).is_ok());
+        assert!(host.session_key().is_some());
+    }
+
+    #[test]
+    fn should_authenticate_with_the_key_length_chosen_per_connection() {
+        let constants = rfc5054_1024();
+        let mut host = AnySrp6::for_group(&constants).unwrap();
+        let mut user = AnySrp6User::for_group(&constants).unwrap();
+        assert_eq!(host.key_length(), 128);
+
+        let details = host.generate_new_user_secrets("Bob", "secret-password", &constants);
+        let user_handshake = user.start_handshake("Bob", &constants);
+        let server_handshake = host
+            .continue_handshake(&details, &user_handshake, &constants)
+            .unwrap();
+        let proof = user
+            .update_handshake(&server_handshake, &constants, "Bob", "secret-password")
+            .unwrap();
+        let strong_proof = host.verify_proof(&proof).unwrap();
+
+        let (_, session_key) = user.verify_proof(&strong_proof).unwrap();
+        assert_eq!(host.session_key(), Some(&session_key));
+    }
+
+    #[test]
+    fn should_find_the_group_of_a_stored_verifier() {
+        let constants = rfc5054_1024();
+        let details =
+            Srp6::<128, 128>::new().generate_new_user_secrets("Bob", "secret-password", &constants);
+        let stored = details.verifier.to_array_pad_zero::<128>();
+
+        let group = group_for_verifier_len(stored.len()).unwrap();
+        assert_eq!(group, constants.group_info());
+        assert_eq!(group.name(), Some("rfc5054-1024"));
+        #[cfg(feature = "group-2048")]
+        assert_eq!(
+            group_for_verifier_len(256).and_then(|group| group.name()),
+            Some("rfc5054-2048")
+        );
+        assert_eq!(group_for_verifier_len(100), None);
+    }
+
+    #[test]
+    fn should_refuse_key_lengths_without_a_variant() {
+        let constants = OpenConstants {
+            module: PrimeModulus::from(23),
+            generator: Generator::from(5),
+        };
+
+        assert_eq!(
+            AnySrp6::for_group(&constants).err(),
+            Some(Srp6Error::KeyLengthMismatch {
+                given: 1,
+                expected: 512
+            })
+        );
+    }
+}
@@ -0,0 +1,158 @@
+/*!
+Reads and writes one handshake message at a time over any [`Read`] / [`Write`] stream.
+
+Each message is one or more frames, a frame is a `u32` length in big endian followed
+by the payload, larger frames than [`MAX_FRAME_LENGTH`] are refused before they are read:
+- [`UserHandshake`]: the username (UTF-8), then `PAD(A)`
+- [`ServerHandshake`]: `PAD(s) | PAD(B)`, see [`ServerHandshake::to_concat_bytes`]
+
+A stream that ends early fails with [`Srp6Error::Io`] of [`ErrorKind::UnexpectedEof`].
+*/
+use std::io::{ErrorKind, Read, Write};
+
+use crate::primitives::*;
+use crate::{Result, Srp6Error};
+
+/// larger frames are refused before they are read, the largest message is `PAD(s) | PAD(B)`
+pub(crate) const MAX_FRAME_LENGTH: usize = 4096;
+
+impl UserHandshake {
+    /// writes the username and `A` as two frames
+    pub fn write_to<const KEY_LENGTH: usize, W: Write>(&self, writer: &mut W) -> Result<()> {
+        write_frame(writer, self.username.as_bytes())?;
+        write_frame(
+            writer,
+            &self.user_publickey.to_array_pad_zero::<KEY_LENGTH>(),
+        )
+    }
+
+    /// reads exactly one [`UserHandshake`] as written by [`UserHandshake::write_to`]
+    pub fn read_from<const KEY_LENGTH: usize, R: Read>(reader: &mut R) -> Result<Self> {
+        let username = String::from_utf8(read_frame(reader)?)
+            .map_err(|_| Srp6Error::Io(ErrorKind::InvalidData))?;
+        let user_publickey = PublicKey::from_bytes_le(&read_exact_frame(reader, KEY_LENGTH)?);
+
+        Ok(Self {
+            username,
+            user_publickey,
+        })
+    }
+}
+
+impl ServerHandshake {
+    /// writes `PAD(s) | PAD(B)` as one frame
+    pub fn write_to<const KEY_LENGTH: usize, const SALT_LENGTH: usize, W: Write>(
+        &self,
+        writer: &mut W,
+    ) -> Result<()> {
+        write_frame(writer, &self.to_concat_bytes::<KEY_LENGTH, SALT_LENGTH>())
+    }
+
+    /// reads exactly one [`ServerHandshake`] as written by [`ServerHandshake::write_to`]
+    pub fn read_from<const KEY_LENGTH: usize, const SALT_LENGTH: usize, R: Read>(
+        reader: &mut R,
+    ) -> Result<Self> {
+        Self::from_concat_bytes::<KEY_LENGTH, SALT_LENGTH>(&read_frame(reader)?)
+    }
+}
+
+pub(crate) fn write_frame<W: Write>(writer: &mut W, payload: &[u8]) -> Result<()> {
+    writer.write_all(&(payload.len() as u32).to_be_bytes())?;
+    writer.write_all(payload)?;
+    writer.flush()?;
+
+    Ok(())
+}
+
+pub(crate) fn read_frame<R: Read>(reader: &mut R) -> Result<Vec<u8>> {
+    let mut length = [0_u8; 4];
+    reader.read_exact(&mut length)?;
+    let length = u32::from_be_bytes(length) as usize;
+    if length > MAX_FRAME_LENGTH {
+        return Err(Srp6Error::Io(ErrorKind::InvalidData));
+    }
+    let mut payload = vec![0_u8; length];
+    reader.read_exact(&mut payload)?;
+
+    Ok(payload)
+}
+
+/// a frame of exactly `expected` bytes, e.g. a padded number
+pub(crate) fn read_exact_frame<R: Read>(reader: &mut R, expected: usize) -> Result<Vec<u8>> {
+    let payload = read_frame(reader)?;
+    if payload.len() != expected {
+        return Err(Srp6Error::KeyLengthMismatch {
+            given: payload.len(),
+            expected,
+        });
+    }
+
+    Ok(payload)
+}
+
+#[cfg(test)]
+#[cfg(feature = "group-1024")]
+mod tests {
+    use super::*;
+    use crate::{rfc5054_1024, HostAPI, Srp6, Srp6User, UserTrait};
+
+    type Srp6_1024 = Srp6<128, 128>;
+
+    #[test]
+    fn should_read_back_one_handshake_at_a_time() {
+        let constants = rfc5054_1024();
+        let mut host = Srp6_1024::new();
+        let details = host.generate_new_user_secrets("Bob", "secret-password", &constants);
+        let user_handshake = Srp6User::<128, 128>::new().start_handshake("Bob", &constants);
+        let server_handshake = host
+            .continue_handshake(&details, &user_handshake, &constants)
+            .unwrap();
+
+        let mut stream = Vec::new();
+        user_handshake.write_to::<128, _>(&mut stream).unwrap();
+        server_handshake
+            .write_to::<128, 128, _>(&mut stream)
+            .unwrap();
+
+        let mut reader = stream.as_slice();
+        assert_eq!(
+            UserHandshake::read_from::<128, _>(&mut reader).unwrap(),
+            user_handshake
+        );
+        assert_eq!(
+            ServerHandshake::read_from::<128, 128, _>(&mut reader).unwrap(),
+            server_handshake
+        );
+        assert!(reader.is_empty());
+    }
+
+    #[test]
+    fn should_fail_on_short_or_oversized_frames() {
+        let user_handshake = UserHandshake {
+            username: "Bob".to_owned(),
+            user_publickey: PublicKey::from(42),
+        };
+        let mut stream = Vec::new();
+        user_handshake.write_to::<128, _>(&mut stream).unwrap();
+
+        for end in [0, 2, 7, stream.len() - 1].iter() {
+            assert_eq!(
+                UserHandshake::read_from::<128, _>(&mut &stream[..*end]),
+                Err(Srp6Error::Io(ErrorKind::UnexpectedEof))
+            );
+        }
+        assert_eq!(
+            UserHandshake::read_from::<64, _>(&mut stream.as_slice()),
+            Err(Srp6Error::KeyLengthMismatch {
+                given: 128,
+                expected: 64
+            })
+        );
+
+        let oversized = ((MAX_FRAME_LENGTH + 1) as u32).to_be_bytes();
+        assert_eq!(
+            ServerHandshake::read_from::<128, 128, _>(&mut &oversized[..]),
+            Err(Srp6Error::Io(ErrorKind::InvalidData))
+        );
+    }
+}
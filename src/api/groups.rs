@@ -0,0 +1,472 @@
+/*!
+Preconfigured groups (`N` and `g`) taken from [RFC5054] and a [`GroupRegistry`]
+that allows to refer to a group by a short [`GroupId`] instead of the full modulus.
+
+Each group is behind a feature, e.g. `group-2048`, all are enabled by default.
+
+[RFC5054]: https://datatracker.ietf.org/doc/html/rfc5054#appendix-A
+*/
+use std::collections::HashMap;
+
+use crate::hash::{Digest, Hash, HashFunc, Update};
+use crate::primitives::{Generator, OpenConstants, PrimeModulus};
+use crate::{Result, Srp6Error};
+use serde::Serialize;
+
+/// Refers to the hash of a group, see [`OpenConstants::group_id`]
+pub type GroupId = Hash;
+
+/// strength and [`GroupId`] of the group a handshake used, e.g. for audit logs
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize)]
+pub struct GroupInfo {
+    /// bit length of `N`
+    pub bits: usize,
+    pub id: GroupId,
+}
+
+impl GroupInfo {
+    /// estimated security level in bits of a group with a modulus of `bits`,
+    /// see [`GroupInfo::security_level`]
+    pub const fn security_level_for(bits: usize) -> usize {
+        match bits {
+            15360.. => 256,
+            7680.. => 192,
+            3072.. => 128,
+            2048.. => 112,
+            1024.. => 80,
+            _ => 0,
+        }
+    }
+
+    /// estimated security level in bits, as in the table of [NIST SP 800-57 Part 1]
+    /// for finite field groups, e.g. 112 for 2048 bit and 128 for 3072 bit,
+    /// the level of the next smaller listed size for others and 0 below 1024 bit
+    ///
+    /// [NIST SP 800-57 Part 1]: https://nvlpubs.nist.gov/nistpubs/SpecialPublications/NIST.SP.800-57pt1r5.pdf
+    pub const fn security_level(&self) -> usize {
+        Self::security_level_for(self.bits)
+    }
+}
+
+/// Miller-Rabin rounds for [`OpenConstants::require_safe_prime`]
+const SAFE_PRIME_ROUNDS: usize = 32;
+
+/// the 1024 bit group from [RFC5054]
+///
+/// [RFC5054]: https://datatracker.ietf.org/doc/html/rfc5054#appendix-A
+#[cfg(feature = "group-1024")]
+pub fn rfc5054_1024() -> OpenConstants {
+    OpenConstants {
+        generator: Generator::from(2),
+        module: PrimeModulus::from_bytes_be(&hex_literal::hex!(
+            "EEAF0AB9 ADB38DD6 9C33F80A FA8FC5E8 60726187 75FF3C0B 9EA2314C
+            9C256576 D674DF74 96EA81D3 383B4813 D692C6E0 E0D5D8E2 50B98BE4
+            8E495C1D 6089DAD1 5DC7D7B4 6154D6B6 CE8EF4AD 69B15D49 82559B29
+            7BCF1885 C529F566 660E57EC 68EDBC3C 05726CC0 2FD4CBF4 976EAA9A
+            FD5138FE 8376435B 9FC61D2F C0EB06E3"
+        )),
+    }
+}
+
+/// the 1536 bit group from [RFC5054]
+///
+/// [RFC5054]: https://datatracker.ietf.org/doc/html/rfc5054#appendix-A
+#[cfg(feature = "group-1536")]
+pub fn rfc5054_1536() -> OpenConstants {
+    OpenConstants {
+        generator: Generator::from(2),
+        module: PrimeModulus::from_bytes_be(&hex_literal::hex!(
+            "9DEF3CAF B939277A B1F12A86 17A47BBB DBA51DF4 99AC4C80 BEEEA961
+            4B19CC4D 5F4F5F55 6E27CBDE 51C6A94B E4607A29 1558903B A0D0F843
+            80B655BB 9A22E8DC DF028A7C EC67F0D0 8134B1C8 B9798914 9B609E0B
+            E3BAB63D 47548381 DBC5B1FC 764E3F4B 53DD9DA1 158BFD3E 2B9C8CF5
+            6EDF0195 39349627 DB2FD53D 24B7C486 65772E43 7D6C7F8C E442734A
+            F7CCB7AE 837C264A E3A9BEB8 7F8A2FE9 B8B5292E 5A021FFF 5E91479E
+            8CE7A28C 2442C6F3 15180F93 499A234D CF76E3FE D135F9BB"
+        )),
+    }
+}
+
+/// the 2048 bit group from [RFC5054]
+///
+/// [RFC5054]: https://datatracker.ietf.org/doc/html/rfc5054#appendix-A
+#[cfg(feature = "group-2048")]
+pub fn rfc5054_2048() -> OpenConstants {
+    OpenConstants {
+        generator: Generator::from(2),
+        module: PrimeModulus::from_bytes_be(&hex_literal::hex!(
+            "AC6BDB41 324A9A9B F166DE5E 1389582F AF72B665 1987EE07 FC319294
+            3DB56050 A37329CB B4A099ED 8193E075 7767A13D D52312AB 4B03310D
+            CD7F48A9 DA04FD50 E8083969 EDB767B0 CF609517 9A163AB3 661A05FB
+            D5FAAAE8 2918A996 2F0B93B8 55F97993 EC975EEA A80D740A DBF4FF74
+            7359D041 D5C33EA7 1D281E44 6B14773B CA97B43A 23FB8016 76BD207A
+            436C6481 F1D2B907 8717461A 5B9D32E6 88F87748 544523B5 24B0D57D
+            5EA77A27 75D2ECFA 032CFBDB F52FB378 61602790 04E57AE6 AF874E73
+            03CE5329 9CCC041C 7BC308D8 2A5698F3 A8D0C382 71AE35F8 E9DBFBB6
+            94B5C803 D89F7AE4 35DE236D 525F5475 9B65E372 FCD68EF2 0FA7111F
+            9E4AFF73"
+        )),
+    }
+}
+
+/// the 3072 bit group from [RFC5054]
+///
+/// [RFC5054]: https://datatracker.ietf.org/doc/html/rfc5054#appendix-A
+#[cfg(feature = "group-3072")]
+pub fn rfc5054_3072() -> OpenConstants {
+    OpenConstants {
+        generator: Generator::from(5),
+        module: PrimeModulus::from_bytes_be(&hex_literal::hex!(
+            "FFFFFFFF FFFFFFFF C90FDAA2 2168C234 C4C6628B 80DC1CD1 29024E08
+            8A67CC74 020BBEA6 3B139B22 514A0879 8E3404DD EF9519B3 CD3A431B
+            302B0A6D F25F1437 4FE1356D 6D51C245 E485B576 625E7EC6 F44C42E9
+            A637ED6B 0BFF5CB6 F406B7ED EE386BFB 5A899FA5 AE9F2411 7C4B1FE6
+            49286651 ECE45B3D C2007CB8 A163BF05 98DA4836 1C55D39A 69163FA8
+            FD24CF5F 83655D23 DCA3AD96 1C62F356 208552BB 9ED52907 7096966D
+            670C354E 4ABC9804 F1746C08 CA18217C 32905E46 2E36CE3B E39E772C
+            180E8603 9B2783A2 EC07A28F B5C55DF0 6F4C52C9 DE2BCBF6 95581718
+            3995497C EA956AE5 15D22618 98FA0510 15728E5A 8AAAC42D AD33170D
+            04507A33 A85521AB DF1CBA64 ECFB8504 58DBEF0A 8AEA7157 5D060C7D
+            B3970F85 A6E1E4C7 ABF5AE8C DB0933D7 1E8C94E0 4A25619D CEE3D226
+            1AD2EE6B F12FFA06 D98A0864 D8760273 3EC86A64 521F2B18 177B200C
+            BBE11757 7A615D6C 770988C0 BAD946E2 08E24FA0 74E5AB31 43DB5BFC
+            E0FD108E 4B82D120 A93AD2CA FFFFFFFF FFFFFFFF"
+        )),
+    }
+}
+
+/// the 4096 bit group from [RFC5054]
+///
+/// [RFC5054]: https://datatracker.ietf.org/doc/html/rfc5054#appendix-A
+#[cfg(feature = "group-4096")]
+pub fn rfc5054_4096() -> OpenConstants {
+    OpenConstants {
+        generator: Generator::from(5),
+        module: PrimeModulus::from_bytes_be(&hex_literal::hex!(
+            "FFFFFFFF FFFFFFFF C90FDAA2 2168C234 C4C6628B 80DC1CD1 29024E08
+            8A67CC74 020BBEA6 3B139B22 514A0879 8E3404DD EF9519B3 CD3A431B
+            302B0A6D F25F1437 4FE1356D 6D51C245 E485B576 625E7EC6 F44C42E9
+            A637ED6B 0BFF5CB6 F406B7ED EE386BFB 5A899FA5 AE9F2411 7C4B1FE6
+            49286651 ECE45B3D C2007CB8 A163BF05 98DA4836 1C55D39A 69163FA8
+            FD24CF5F 83655D23 DCA3AD96 1C62F356 208552BB 9ED52907 7096966D
+            670C354E 4ABC9804 F1746C08 CA18217C 32905E46 2E36CE3B E39E772C
+            180E8603 9B2783A2 EC07A28F B5C55DF0 6F4C52C9 DE2BCBF6 95581718
+            3995497C EA956AE5 15D22618 98FA0510 15728E5A 8AAAC42D AD33170D
+            04507A33 A85521AB DF1CBA64 ECFB8504 58DBEF0A 8AEA7157 5D060C7D
+            B3970F85 A6E1E4C7 ABF5AE8C DB0933D7 1E8C94E0 4A25619D CEE3D226
+            1AD2EE6B F12FFA06 D98A0864 D8760273 3EC86A64 521F2B18 177B200C
+            BBE11757 7A615D6C 770988C0 BAD946E2 08E24FA0 74E5AB31 43DB5BFC
+            E0FD108E 4B82D120 A9210801 1A723C12 A787E6D7 88719A10 BDBA5B26
+            99C32718 6AF4E23C 1A946834 B6150BDA 2583E9CA 2AD44CE8 DBBBC2DB
+            04DE8EF9 2E8EFC14 1FBECAA6 287C5947 4E6BC05D 99B2964F A090C3A2
+            233BA186 515BE7ED 1F612970 CEE2D7AF B81BDD76 2170481C D0069127
+            D5B05AA9 93B4EA98 8D8FDDC1 86FFB7DC 90A6C08F 4DF435C9 34063199
+            FFFFFFFF FFFFFFFF"
+        )),
+    }
+}
+
+impl OpenConstants {
+    /// group parameters from DER encoded INTEGERs, e.g. from a certificate extension
+    ///
+    /// `key_length` is the expected length of `N` in bytes, e.g. 256 for a 2048 bit group
+    #[allow(non_snake_case)]
+    pub fn from_der(N: &[u8], g: &[u8], key_length: usize) -> Result<Self> {
+        let module = PrimeModulus::from_der(N).map_err(|_| Srp6Error::InvalidDerEncoding)?;
+        let generator = Generator::from_der(g).map_err(|_| Srp6Error::InvalidDerEncoding)?;
+        if module.byte_len() != key_length {
+            return Err(Srp6Error::KeyLengthMismatch {
+                given: module.byte_len(),
+                expected: key_length,
+            });
+        }
+
+        Ok(Self { module, generator })
+    }
+
+    /// strict mode for custom groups, fails with [`Srp6Error::InvalidModulus`]
+    /// unless `N` is a safe prime, i.e. `N` and `(N - 1) / 2` are prime
+    ///
+    /// runs Miller-Rabin on both numbers, so this is expensive and meant to run once on setup
+    pub fn require_safe_prime(self) -> Result<Self> {
+        if !self.module.is_safe_prime(SAFE_PRIME_ROUNDS) {
+            return Err(Srp6Error::InvalidModulus(self.module));
+        }
+
+        Ok(self)
+    }
+
+    /// the [`GroupInfo`] of this group
+    pub fn group_info(&self) -> GroupInfo {
+        GroupInfo {
+            bits: self.module.byte_len() * 8,
+            id: self.group_id(),
+        }
+    }
+
+    /// the [`GroupId`] is the hash of the group parameters
+    ///
+    /// formula: `H(len(N) | N | g)`, the length prefix keeps `N` and `g` apart
+    pub fn group_id(&self) -> GroupId {
+        HashFunc::new()
+            .chain((self.module.num_bytes() as u32).to_le_bytes())
+            .chain(self.module.to_vec())
+            .chain(self.generator.to_vec())
+            .finalize()
+            .into()
+    }
+
+    /// pins the group on the client, fails with [`Srp6Error::GroupMismatch`] unless
+    /// `N` and `g` are exactly `expected_N` and `expected_g`
+    ///
+    /// call it on the group received from the server before the proof is calculated,
+    /// so a substituted group is refused
+    #[allow(non_snake_case)]
+    pub fn verify_group(&self, expected_N: &PrimeModulus, expected_g: &Generator) -> Result<()> {
+        if &self.module != expected_N || &self.generator != expected_g {
+            return Err(Srp6Error::GroupMismatch);
+        }
+
+        Ok(())
+    }
+}
+
+/// Maps a [`GroupId`] to its group parameters, preloaded with the enabled [RFC5054] groups
+///
+/// **NOTE**: there is no compact handshake format (`Handshake::from_compact`) yet, the
+/// registry is for applications that send the [`GroupId`] instead of `N` and `g` themselves
+///
+/// [RFC5054]: https://datatracker.ietf.org/doc/html/rfc5054#appendix-A
+#[derive(Debug, Clone)]
+pub struct GroupRegistry {
+    groups: HashMap<GroupId, OpenConstants>,
+}
+
+impl GroupRegistry {
+    #[allow(unused_mut)]
+    pub fn new() -> Self {
+        let mut registry = Self {
+            groups: HashMap::new(),
+        };
+        #[cfg(feature = "group-1024")]
+        registry.insert(rfc5054_1024());
+        #[cfg(feature = "group-1536")]
+        registry.insert(rfc5054_1536());
+        #[cfg(feature = "group-2048")]
+        registry.insert(rfc5054_2048());
+        #[cfg(feature = "group-3072")]
+        registry.insert(rfc5054_3072());
+        #[cfg(feature = "group-4096")]
+        registry.insert(rfc5054_4096());
+
+        registry
+    }
+
+    /// registers a custom group and returns its [`GroupId`]
+    #[allow(non_snake_case)]
+    pub fn register(&mut self, N: PrimeModulus, g: Generator) -> GroupId {
+        self.insert(OpenConstants {
+            module: N,
+            generator: g,
+        })
+    }
+
+    /// looks up the group parameters for a [`GroupId`]
+    pub fn lookup(&self, id: &GroupId) -> Option<&OpenConstants> {
+        self.groups.get(id)
+    }
+
+    fn insert(&mut self, constants: OpenConstants) -> GroupId {
+        let id = constants.group_id();
+        self.groups.insert(id, constants);
+
+        id
+    }
+}
+
+impl Default for GroupRegistry {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_have_the_right_bit_length() {
+        #[cfg(feature = "group-1024")]
+        assert_eq!(rfc5054_1024().module.num_bytes() * 8, 1024);
+        #[cfg(feature = "group-1536")]
+        assert_eq!(rfc5054_1536().module.num_bytes() * 8, 1536);
+        #[cfg(feature = "group-2048")]
+        assert_eq!(rfc5054_2048().module.num_bytes() * 8, 2048);
+        #[cfg(feature = "group-3072")]
+        assert_eq!(rfc5054_3072().module.num_bytes() * 8, 3072);
+        #[cfg(feature = "group-4096")]
+        assert_eq!(rfc5054_4096().module.num_bytes() * 8, 4096);
+    }
+
+    #[test]
+    fn should_match_the_key_length_of_the_const_generic_api() {
+        #[cfg(feature = "group-2048")]
+        assert_eq!(crate::Srp6_2048::KEY_LEN, rfc5054_2048().module.byte_len());
+        #[cfg(feature = "group-4096")]
+        assert_eq!(crate::Srp6_4096::KEY_LEN, rfc5054_4096().module.byte_len());
+    }
+
+    #[test]
+    fn should_accept_the_rfc5054_groups_in_strict_mode() {
+        #[cfg(feature = "group-1024")]
+        assert!(rfc5054_1024().require_safe_prime().is_ok());
+        #[cfg(feature = "group-2048")]
+        assert!(rfc5054_2048().require_safe_prime().is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "group-1024")]
+    fn should_reject_moduli_that_are_not_safe_primes_in_strict_mode() {
+        // 2^127 - 1 is prime, but (2^127 - 2) / 2 is even
+        let mersenne = PrimeModulus::from_hex_str_be("7FFFFFFFFFFFFFFFFFFFFFFFFFFFFFFF").unwrap();
+        let composite = &rfc5054_1024().module + &PrimeModulus::from(2);
+        for module in [mersenne, composite] {
+            let constants = OpenConstants {
+                module: module.clone(),
+                generator: Generator::from(2),
+            };
+            assert_eq!(
+                constants.require_safe_prime().err(),
+                Some(Srp6Error::InvalidModulus(module))
+            );
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "group-2048")]
+    fn should_lookup_preloaded_groups() {
+        let registry = GroupRegistry::new();
+        let constants = rfc5054_2048();
+        let found = registry.lookup(&constants.group_id()).unwrap();
+
+        assert_eq!(found.module, constants.module);
+        assert_eq!(found.generator, constants.generator);
+    }
+
+    #[test]
+    fn should_register_a_custom_group() {
+        let mut registry = GroupRegistry::new();
+        let id = registry.register(PrimeModulus::from(23), Generator::from(5));
+
+        let found = registry.lookup(&id).unwrap();
+        assert_eq!(found.module, PrimeModulus::from(23));
+        assert_eq!(found.generator, Generator::from(5));
+        #[cfg(feature = "group-1024")]
+        assert_ne!(id, rfc5054_1024().group_id());
+    }
+
+    #[test]
+    #[cfg(feature = "group-2048")]
+    fn should_parse_der_encoded_groups() {
+        let mut N = vec![0x02, 0x82, 0x01, 0x01, 0x00];
+        N.extend(rfc5054_2048().module.to_bytes_be());
+        let g = [0x02, 0x01, 0x02];
+
+        let constants = OpenConstants::from_der(&N, &g, 256).unwrap();
+        assert_eq!(constants.module, rfc5054_2048().module);
+        assert_eq!(constants.generator, rfc5054_2048().generator);
+
+        assert_eq!(
+            OpenConstants::from_der(&N, &g, 512).err(),
+            Some(Srp6Error::KeyLengthMismatch {
+                given: 256,
+                expected: 512
+            })
+        );
+        assert_eq!(
+            OpenConstants::from_der(&N[1..], &g, 256).err(),
+            Some(Srp6Error::InvalidDerEncoding)
+        );
+    }
+
+    #[test]
+    fn should_estimate_the_security_level() {
+        let level = |bits| GroupInfo { bits, id: [0; 20] }.security_level();
+
+        assert_eq!(level(512), 0);
+        assert_eq!(level(1024), 80);
+        assert_eq!(level(1536), 80);
+        assert_eq!(level(2048), 112);
+        assert_eq!(level(3072), 128);
+        assert_eq!(level(4096), 128);
+        assert_eq!(level(8192), 192);
+        assert_eq!(level(15360), 256);
+        #[cfg(feature = "group-1024")]
+        assert_eq!(rfc5054_1024().group_info().security_level(), 80);
+    }
+
+    #[test]
+    #[cfg(feature = "group-1024")]
+    fn should_verify_a_pinned_group() {
+        let pinned = rfc5054_1024();
+        let received = rfc5054_1024();
+        assert!(received
+            .verify_group(&pinned.module, &pinned.generator)
+            .is_ok());
+
+        let other_generator = OpenConstants {
+            generator: Generator::from(5),
+            ..rfc5054_1024()
+        };
+        let other_module = OpenConstants {
+            module: &pinned.module - &PrimeModulus::from(2),
+            ..rfc5054_1024()
+        };
+        for received in [other_generator, other_module] {
+            assert_eq!(
+                received.verify_group(&pinned.module, &pinned.generator),
+                Err(Srp6Error::GroupMismatch)
+            );
+        }
+    }
+
+    #[test]
+    fn should_calculate_the_pinned_multiplier_of_every_group() {
+        #[allow(dead_code)]
+        fn check<const KEY_LENGTH: usize>(constants: &OpenConstants) {
+            let k = crate::Srp6::<KEY_LENGTH, 16>::new().multiplier(constants);
+            assert_eq!(
+                Some(k.to_string().as_str()),
+                crate::Srp6::<KEY_LENGTH, 16>::pinned_multiplier_hex()
+            );
+        }
+
+        #[cfg(feature = "group-1024")]
+        check::<128>(&rfc5054_1024());
+        #[cfg(feature = "group-1536")]
+        check::<192>(&rfc5054_1536());
+        #[cfg(feature = "group-2048")]
+        check::<256>(&rfc5054_2048());
+        #[cfg(feature = "group-3072")]
+        check::<384>(&rfc5054_3072());
+        #[cfg(feature = "group-4096")]
+        check::<512>(&rfc5054_4096());
+        assert_eq!(crate::Srp6::<64, 16>::pinned_multiplier_hex(), None);
+    }
+
+    #[test]
+    fn should_not_find_unknown_groups() {
+        let registry = GroupRegistry::new();
+        assert!(registry.lookup(&[0_u8; 20]).is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "group-1024")]
+    fn should_distinguish_generators_of_the_same_modulus() {
+        let mut registry = GroupRegistry::new();
+        let g5 = registry.register(rfc5054_1024().module, Generator::from(5));
+
+        assert_ne!(g5, rfc5054_1024().group_id());
+    }
+}
@@ -0,0 +1,73 @@
+/*!
+Authenticates application messages after the handshake with a MAC key derived from `K`.
+
+The first message after the exchange is often MAC'd to confirm the handshake and bind
+its payload to the session. [`StrongSessionKey::mac`] and [`StrongSessionKey::verify_mac`]
+use a key derived from the [`StrongSessionKey`] `K`, never `K` itself:
+- MAC key: `HKDF-Expand(HKDF-Extract(0, PAD(K)), "srp6 key confirmation")`, [`HASH_LENGTH`] bytes
+- tag: `HMAC(MAC key, data)`
+
+The same key is used in both directions, to tell them apart use
+[`derive_directional_keys`](crate::derive_directional_keys) instead.
+*/
+use hmac::{Hmac, Mac};
+
+use crate::hash::{HashFunc, HASH_LENGTH};
+use crate::primitives::StrongSessionKey;
+
+type HmacFunc = Hmac<HashFunc>;
+
+const KEY_CONFIRMATION_LABEL: &[u8] = b"srp6 key confirmation";
+
+impl StrongSessionKey {
+    /// the tag of `data`, `HMAC(MAC key, data)` with the MAC key derived from `K`
+    pub fn mac(&self, data: &[u8]) -> Vec<u8> {
+        self.data_mac(data).finalize().into_bytes().to_vec()
+    }
+
+    /// whether `tag` is the [`StrongSessionKey::mac`] of `data`, compared in constant time
+    pub fn verify_mac(&self, data: &[u8], tag: &[u8]) -> bool {
+        self.data_mac(data).verify_slice(tag).is_ok()
+    }
+
+    fn data_mac(&self, data: &[u8]) -> HmacFunc {
+        let mut mac_key = [0_u8; HASH_LENGTH];
+        self.derive_into(&[KEY_CONFIRMATION_LABEL], &mut mac_key);
+        let mut mac = HmacFunc::new_from_slice(&mac_key).expect("HMAC accepts keys of any length");
+        mac.update(data);
+
+        mac
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use crate::primitives::STRONG_SESSION_KEY_LENGTH;
+
+    #[test]
+    fn should_verify_the_mac_of_the_same_session() {
+        let K = StrongSessionKey::from(42);
+        let tag = K.mac(b"first message");
+
+        assert_eq!(tag.len(), HASH_LENGTH);
+        assert!(K.verify_mac(b"first message", &tag));
+        assert!(!K.verify_mac(b"other message", &tag));
+        assert!(!StrongSessionKey::from(43).verify_mac(b"first message", &tag));
+        assert!(!K.verify_mac(b"first message", &tag[..HASH_LENGTH - 1]));
+    }
+
+    #[test]
+    fn should_not_use_the_session_key_as_mac_key() {
+        let K = StrongSessionKey::from(42);
+        let mut with_K =
+            HmacFunc::new_from_slice(&K.to_array_pad_zero::<STRONG_SESSION_KEY_LENGTH>()).unwrap();
+        with_K.update(b"first message");
+
+        assert_ne!(
+            K.mac(b"first message"),
+            with_K.finalize().into_bytes().to_vec()
+        );
+    }
+}
@@ -0,0 +1,47 @@
+/*!
+The one construction every key derived from the [`StrongSessionKey`] `K` goes through:
+
+formula: `HKDF-Expand(HKDF-Extract(0, PAD(K)), label | context)`
+
+The label names the purpose, e.g. `"srp6 session binding"`, so keys for different
+purposes are independent. The optional context binds a key to e.g. a challenge.
+*/
+use hkdf::Hkdf;
+
+use crate::hash::HashFunc;
+use crate::primitives::{StrongSessionKey, STRONG_SESSION_KEY_LENGTH};
+
+impl StrongSessionKey {
+    /// fills `key` with the key for `info`, the label followed by any context
+    ///
+    /// **NOTE**: panics for more than `255 * HASH_LENGTH` bytes, callers check longer lengths
+    pub(crate) fn derive_into(&self, info: &[&[u8]], key: &mut [u8]) {
+        Hkdf::<HashFunc>::new(None, &self.to_array_pad_zero::<STRONG_SESSION_KEY_LENGTH>())
+            .expand_multi_info(info, key)
+            .expect("at most 255 * HASH_LENGTH bytes are derived");
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use crate::hash::HASH_LENGTH;
+
+    #[test]
+    fn should_derive_independent_keys_per_label_and_context() {
+        let K = StrongSessionKey::from(42);
+        let derive = |K: &StrongSessionKey, info: &[&[u8]]| {
+            let mut key = [0_u8; HASH_LENGTH];
+            K.derive_into(info, &mut key);
+            key
+        };
+
+        let key = derive(&K, &[b"srp6 a"]);
+        assert_eq!(key, derive(&K, &[b"srp6 a"]));
+        assert_eq!(key, derive(&K, &[b"srp6 ", b"a"]));
+        assert_ne!(key, derive(&K, &[b"srp6 b"]));
+        assert_ne!(key, derive(&K, &[b"srp6 a", b"context"]));
+        assert_ne!(key, derive(&StrongSessionKey::from(43), &[b"srp6 a"]));
+    }
+}
@@ -1,15 +1,26 @@
 // use super::user::{HandshakeProof, StrongProofVerifier};
+use crate::big_number::Zero;
+use crate::hash::HASH_NAME;
 use crate::primitives::*;
-use crate::{Result};
-use serde::Serialize;
+use crate::telemetry::{self, Side, Verification};
 use crate::Srp6Error;
+use crate::{Context, Result};
+use crate::{GroupId, GroupInfo};
+use rand::{thread_rng, CryptoRng, RngCore};
+use serde::Serialize;
+use std::fmt::{Debug, Display, Formatter};
 // use crate::big_number::BigNumber;
 
-use log::debug;
+use log::warn;
 
 /// this trait provides a higher level api
 pub trait HostAPI<const KL: usize, const SL: usize> {
     /// for new users, or if they recover their password
+    ///
+    /// Empty, single character or whitespace only usernames and passwords are accepted and
+    /// authenticate like any other input, they are hashed as is without trimming, such
+    /// records pass [`Srp6::validate_user`] as well.
+    /// Use [`HostAPI::generate_new_user_secrets_checked`] to refuse weak passwords.
     #[allow(non_snake_case)]
     fn generate_new_user_secrets(
         &mut self,
@@ -18,34 +29,97 @@ pub trait HostAPI<const KL: usize, const SL: usize> {
         constants: &OpenConstants,
     ) -> UserDetails;
 
+    /// same as [`HostAPI::generate_new_user_secrets`], but the salt is drawn from `rng`
+    #[allow(non_snake_case)]
+    fn generate_new_user_secrets_with_rng<R: RngCore + CryptoRng>(
+        &mut self,
+        I: UsernameRef,
+        p: &ClearTextPassword,
+        constants: &OpenConstants,
+        rng: &mut R,
+    ) -> UserDetails;
+
+    /// same as [`HostAPI::generate_new_user_secrets`], but refuses passwords
+    /// that fail the `policy` (too short, in a breach list, ...) with [`Srp6Error::WeakPassword`]
+    #[allow(non_snake_case)]
+    fn generate_new_user_secrets_checked<P: Fn(&ClearTextPassword) -> bool>(
+        &mut self,
+        I: UsernameRef,
+        p: &ClearTextPassword,
+        constants: &OpenConstants,
+        policy: P,
+    ) -> Result<UserDetails>;
+
+    /// generates a fresh [`Salt`] and [`PasswordVerifier`] per group, e.g. to let clients
+    /// on an old and a new group authenticate while migrating.
+    /// `p` is hashed once per group, as `v` depends on `N` and `g`.
+    #[allow(non_snake_case)]
+    fn generate_multi_group_secrets(
+        &mut self,
+        I: UsernameRef,
+        p: &ClearTextPassword,
+        groups: &[OpenConstants],
+    ) -> Vec<(GroupId, Salt, PasswordVerifier)>;
+
     /// starts the handshake with the client
+    ///
+    /// fails with [`Srp6Error::UsernameMismatch`] if `user_details` belong to another user
     fn continue_handshake(
-        &mut self, 
-        user_details: &UserDetails, user_handshake: &UserHandshake, 
-        constants: &OpenConstants)
-        -> Result<ServerHandshake>;
+        &mut self,
+        user_details: &UserDetails,
+        user_handshake: &UserHandshake,
+        constants: &OpenConstants,
+    ) -> Result<ServerHandshake>;
 
-    fn verify_proof(
+    /// same as [`HostAPI::continue_handshake`], but hands the host state to `persist`
+    /// before the [`ServerHandshake`] is returned, e.g. to store it in a session store
+    fn continue_handshake_with_persist<F: FnMut(&Self)>(
         &mut self,
-        users_proof: &Proof
-    ) -> Result<Proof>;
+        user_details: &UserDetails,
+        user_handshake: &UserHandshake,
+        constants: &OpenConstants,
+        persist: F,
+    ) -> Result<ServerHandshake>;
+
+    fn verify_proof(&mut self, users_proof: &Proof) -> Result<StrongProof>;
+
+    /// same as [`HostAPI::verify_proof`], but also returns `K` and the clients `A`
+    fn verify_proof_with_session(&mut self, users_proof: &Proof) -> Result<VerifiedSession>;
 }
 
 /// Main interaction point for the server
 #[allow(non_snake_case)]
-#[derive(Debug, Serialize)]
+#[derive(Serialize)]
 pub struct Srp6<const KEY_LENGTH: usize, const SALT_LENGTH: usize> {
     pub A: PublicKey,
     pub B: PublicKey,
+    #[serde(skip)]
     b: PrivateKey,
-    pub U: PublicKey, 
+    pub U: PublicKey,
+    #[serde(skip)]
     verifier: PrivateKey,
     pub salt: Salt,
+    username: Username,
+    #[serde(skip)]
     S: PrivateKey,
+    #[serde(skip)]
     K: SessionKey,
+    #[serde(skip)]
     M: Proof,
-    verified: bool
-
+    verified: bool,
+    multiplier: Option<MultiplierParameter>,
+    identity_hashing: IdentityHashing,
+    identity_separator: Vec<u8>,
+    session_key_serialization: SessionKeySerialization,
+    proof_layout: ProofLayout,
+    accepted_proof_layouts: Vec<ProofLayout>,
+    /// `M` in each of the `accepted_proof_layouts`
+    #[serde(skip)]
+    accepted_M: Vec<Proof>,
+    padding: PaddingPolicy,
+    group: GroupInfo,
+    min_public_key_bits: Option<usize>,
+    warn_on_weak_verifier: bool,
 }
 
 impl<const KEY_LENGTH: usize, const SALT_LENGTH: usize> Srp6<KEY_LENGTH, SALT_LENGTH> {
@@ -53,21 +127,723 @@ impl<const KEY_LENGTH: usize, const SALT_LENGTH: usize> Srp6<KEY_LENGTH, SALT_LE
     pub const SALT_LEN: usize = SALT_LENGTH;
 
     pub fn new() -> Self {
-        Self{
+        Self {
             A: PublicKey::default(),
             B: PublicKey::default(),
             b: PrivateKey::default(),
-            U: PublicKey::default(), 
+            U: PublicKey::default(),
             verifier: PrivateKey::default(),
             salt: Salt::default(),
+            username: Username::default(),
             S: PrivateKey::default(),
             K: SessionKey::default(),
             M: Proof::default(),
-            verified: false
+            verified: false,
+            multiplier: None,
+            identity_hashing: IdentityHashing::default(),
+            identity_separator: DEFAULT_IDENTITY_SEPARATOR.to_vec(),
+            session_key_serialization: SessionKeySerialization::default(),
+            proof_layout: ProofLayout::default(),
+            accepted_proof_layouts: Vec::new(),
+            accepted_M: Vec::new(),
+            padding: PaddingPolicy::default(),
+            group: GroupInfo::default(),
+            min_public_key_bits: None,
+            warn_on_weak_verifier: false,
+        }
+    }
+
+    /// the conventions of SRP-6 implementations that predate SRP-6a, e.g. along [RFC2945],
+    /// the client uses [`Srp6User::legacy_srp6`](crate::Srp6User::legacy_srp6)
+    /// - `k = 3`
+    /// - `S` without leading zero bytes before it is hashed into `K`
+    /// - `x` and `M1` as in [`Srp6::new`]
+    ///
+    /// **NOTE**: there is no preset for [RFC5054] (TLS-SRP) or its drafts, this crate hashes
+    /// numbers in little endian byte order, the RFC in big endian, so no combination of the
+    /// knobs reproduces its Appendix B vectors, see [`crate::testing`]
+    ///
+    /// [RFC2945]: https://datatracker.ietf.org/doc/html/rfc2945
+    /// [RFC5054]: https://datatracker.ietf.org/doc/html/rfc5054
+    pub fn legacy_srp6() -> Self {
+        Self::new()
+            .with_multiplier(MultiplierParameter::from(3))
+            .with_session_key_serialization(SessionKeySerialization::Minimal)
+    }
+
+    /// uses a custom [`MultiplierParameter`] `k` instead of `k = H(N | PAD(g))`,
+    /// e.g. `k = 3` for legacy SRP-6. The client must use the same `k`.
+    pub fn with_multiplier(mut self, k: MultiplierParameter) -> Self {
+        self.multiplier = Some(k);
+        self
+    }
+
+    /// the [`StrongSessionKey`] `K`, available once the users proof is verified
+    pub fn session_key(&self) -> Option<&StrongSessionKey> {
+        if self.verified {
+            Some(&self.K)
+        } else {
+            None
+        }
+    }
+
+    /// aborts the handshake, e.g. on a timeout or a dropped connection, the secrets
+    /// `b`, `v`, `S`, `K` and `M` are overwritten with zeros, the same happens on drop,
+    /// this makes the end of the handshake explicit at the call site
+    ///
+    /// consumes the host, so a late proof can not be verified anymore
+    pub fn abort(mut self) {
+        self.wipe_secrets();
+    }
+
+    fn wipe_secrets(&mut self) {
+        for secret in [
+            &mut self.b,
+            &mut self.verifier,
+            &mut self.S,
+            &mut self.K,
+            &mut self.M,
+        ] {
+            secret.wipe();
+        }
+        for accepted in self.accepted_M.iter_mut() {
+            accepted.wipe();
+        }
+        self.verified = false;
+        #[cfg(test)]
+        WIPED_SECRETS.with(|wiped| {
+            *wiped.borrow_mut() = vec![
+                self.b.clone(),
+                self.verifier.clone(),
+                self.S.clone(),
+                self.K.clone(),
+                self.M.clone(),
+            ]
+        });
+    }
+
+    /// how the username goes into the private key `x`, the client must use the same
+    pub fn with_identity_hashing(mut self, identity_hashing: IdentityHashing) -> Self {
+        self.identity_hashing = identity_hashing;
+        self
+    }
+
+    /// the bytes between `I` and `p` in `H(I | ":" | p)` of [`IdentityHashing::Rfc2945`],
+    /// `b":"` by default, e.g. none for some implementations, the client must use the same
+    pub fn with_identity_separator(mut self, separator: &[u8]) -> Self {
+        self.identity_separator = separator.to_vec();
+        self
+    }
+
+    /// how `S` is serialized before it is hashed into `K`, e.g. [`SessionKeySerialization::Minimal`]
+    /// to interoperate with reference implementations, the client must use the same
+    pub fn with_session_key_serialization(
+        mut self,
+        serialization: SessionKeySerialization,
+    ) -> Self {
+        self.session_key_serialization = serialization;
+        self
+    }
+
+    /// which parts go into the proof `M1`, e.g. for WoW style servers, the client must use the same
+    pub fn with_proof_layout(mut self, layout: ProofLayout) -> Self {
+        self.proof_layout = layout;
+        self
+    }
+
+    /// accepts `M1` also in each of `layouts`, e.g. while clients move to a new
+    /// [`ProofLayout`], none by default, [`Srp6::verify_proof_with_layout`] reports the match
+    ///
+    /// every accepted layout is one more way to match a proof, remove the old one
+    /// once all clients moved on
+    pub fn with_accepted_proof_layouts(mut self, layouts: &[ProofLayout]) -> Self {
+        self.accepted_proof_layouts = layouts.to_vec();
+        self
+    }
+
+    /// which numbers are padded where they are hashed, e.g. to match a peer that hashes
+    /// `A` without leading zeros, all are padded by default, the client must use the same
+    pub fn with_padding(mut self, padding: PaddingPolicy) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// heuristic hardening against clients with a broken generator of `a`, every
+    /// verification of the proof fails with [`Srp6Error::InvalidPublicKey`] if `A`
+    /// has less than `bits` significant bits, e.g. `A = 1` or `A = g`
+    ///
+    /// off by default, a sensible value is half the bits of `N`
+    #[doc(alias = "min_public_key_bits")]
+    pub fn with_min_public_key_bits(mut self, bits: usize) -> Self {
+        self.min_public_key_bits = Some(bits);
+        self
+    }
+
+    /// logs a warning in [`HostAPI::continue_handshake`] for a verifier that `looks_weak`,
+    /// off by default
+    ///
+    /// the check runs on every handshake, the username is not logged, the application
+    /// can find the accounts to re-provision with `looks_weak` over its user store
+    pub fn with_weak_verifier_warning(mut self, enabled: bool) -> Self {
+        self.warn_on_weak_verifier = enabled;
+        self
+    }
+
+    /// estimated security level in bits of the groups with `KEY_LENGTH` bytes,
+    /// see [`GroupInfo::security_level`]
+    pub const fn security_level() -> usize {
+        GroupInfo::security_level_for(KEY_LENGTH * 8)
+    }
+
+    /// refuses groups with a [`Srp6::security_level`] below `bits`
+    /// with [`Srp6Error::WeakGroup`], e.g. `Srp6_1024::new().require_security_level(112)`
+    pub fn require_security_level(self, bits: usize) -> Result<Self> {
+        if Self::security_level() < bits {
+            return Err(Srp6Error::WeakGroup {
+                security_level: Self::security_level(),
+                required: bits,
+            });
+        }
+
+        Ok(self)
+    }
+
+    /// the [`Username`] of the user the handshake was continued for,
+    /// empty before [`HostAPI::continue_handshake`]
+    pub fn username(&self) -> UsernameRef<'_> {
+        &self.username
+    }
+
+    /// the [`MultiplierParameter`] `k` used for the given group
+    pub fn multiplier(&self, constants: &OpenConstants) -> MultiplierParameter {
+        multiplier_or_default::<KEY_LENGTH>(self.multiplier.as_ref(), constants, self.padding)
+    }
+
+    /// the default `k = H(N | PAD(g))` of the RFC5054 group with `KEY_LENGTH` bytes as upper
+    /// case hex, `None` for other lengths
+    ///
+    /// a regression guard only: the values were recorded from this crate, they catch an
+    /// unintended change of the padding or byte order in `k`, but do not prove it is correct
+    ///
+    /// **NOTE**: not the `k` published in [RFC5054] Appendix B, this crate hashes in little
+    /// endian and pads `g` to 32 bytes, the RFC in big endian and to the length of `N`
+    ///
+    /// [RFC5054]: https://datatracker.ietf.org/doc/html/rfc5054#appendix-B
+    pub const fn pinned_multiplier_hex() -> Option<&'static str> {
+        match KEY_LENGTH {
+            128 => Some("CB65499B73F636D2DE0CEA0845418D122E1A51D"),
+            192 => Some("54D4F306C7A51947EBE87FE9C04D2E3646E87065"),
+            256 => Some("E223089C9BE443D7D2A734D58B67443442C96B89"),
+            384 => Some("C240070E64B8CCC2F4A2D2AB9CD795BA5DED2150"),
+            512 => Some("409A99B452DD14A5E1C0D0B7FE006561D8A15001"),
+            _ => None,
+        }
+    }
+
+    /// cheap range check of the clients [`PublicKey`] `A`, `0 < A < N`,
+    /// e.g. for a gateway to drop junk before the modpow heavy [`HostAPI::continue_handshake`],
+    /// which runs this check first as well
+    #[allow(non_snake_case)]
+    pub fn precheck_public_key(A: &PublicKey, constants: &OpenConstants) -> Result<()> {
+        if A.is_zero() || A >= &constants.module {
+            return Err(Srp6Error::InvalidPublicKey(A.clone()));
+        }
+
+        Ok(())
+    }
+
+    /// checks a [`Salt`] e.g. from an untrusted import, before it is used
+    /// - it must not be longer than [`Self::SALT_LEN`]
+    /// - it must not be all `0x00` or all `0xFF`, a sign of a broken generator
+    pub fn validate_salt(salt: &Salt) -> Result<()> {
+        ensure_max_length(salt, SALT_LENGTH)?;
+        if salt.is_zero() || salt.to_vec().iter().all(|b| *b == 0xFF) {
+            return Err(Srp6Error::WeakSalt(salt.clone()));
+        }
+
+        Ok(())
+    }
+
+    /// checks a stored user record e.g. at startup, fails with
+    /// [`Srp6Error::InvalidUserDetails`] naming the first invalid field
+    /// - the `salt` must pass [`Self::validate_salt`]
+    /// - the `verifier` must not be zero or longer than [`Self::KEY_LEN`]
+    ///
+    /// the `username` is not checked, an empty username authenticates like any other,
+    /// see [`HostAPI::generate_new_user_secrets`]
+    pub fn validate_user(user_details: &UserDetails) -> Result<()> {
+        if Self::validate_salt(&user_details.salt).is_err() {
+            return Err(Srp6Error::InvalidUserDetails("salt"));
+        }
+        if user_details.verifier.is_zero() || user_details.verifier.num_bytes() > KEY_LENGTH {
+            return Err(Srp6Error::InvalidUserDetails("verifier"));
+        }
+
+        Ok(())
+    }
+
+    /// the private key `b`, the minimal state to persist for [`Srp6::reconstruct`]
+    pub fn private_key(&self) -> ServerPrivateKey {
+        ServerPrivateKey::from(self.b.clone())
+    }
+
+    /// new [`UserDetails`] for `new_password` that keep the salt of `old_details`,
+    /// for policies that require a new salt use [`HostAPI::generate_new_user_secrets`]
+    ///
+    /// the old password is not required, confirm it by a handshake before
+    pub fn change_password(
+        &self,
+        old_details: &UserDetails,
+        new_password: &ClearTextPassword,
+        constants: &OpenConstants,
+    ) -> UserDetails {
+        let verifier = compute_verifier(
+            self.identity_hashing,
+            &self.identity_separator,
+            &constants.module,
+            &constants.generator,
+            &old_details.salt,
+            &old_details.username,
+            new_password,
+        );
+
+        UserDetails {
+            username: old_details.username.clone(),
+            salt: old_details.salt.clone(),
+            verifier,
+        }
+    }
+
+    /// same as [`HostAPI::generate_new_user_secrets`], but the salt is derived from `seed`
+    /// by [`Salt::from_seed`], so the [`UserDetails`] are the same on every run,
+    /// e.g. for snapshot tests, never use it in production
+    #[cfg(feature = "testing")]
+    #[allow(non_snake_case)]
+    pub fn generate_new_user_secrets_seeded(
+        &mut self,
+        I: UsernameRef,
+        p: &ClearTextPassword,
+        constants: &OpenConstants,
+        seed: u64,
+    ) -> UserDetails {
+        self.salt = Salt::from_seed(seed, SALT_LENGTH);
+        self.verifier = compute_verifier(
+            self.identity_hashing,
+            &self.identity_separator,
+            &constants.module,
+            &constants.generator,
+            &self.salt,
+            I,
+            p,
+        );
+
+        UserDetails {
+            username: I.to_owned(),
+            salt: self.salt.clone(),
+            verifier: self.verifier.clone(),
+        }
+    }
+
+    /// **DANGER**: test only, skips the proof check, never use it in production
+    ///
+    /// the [`StrongSessionKey`] `K` this host would derive for the clients `A`, with `b` and `B`
+    /// of the current handshake, without the clients proof `M1`, e.g. to build a client with
+    /// a mismatching key on purpose and check that [`HostAPI::verify_proof`] rejects it
+    ///
+    /// fails with [`Srp6Error::InvalidPublicKey`] if `A % N` is zero
+    #[cfg(feature = "testing")]
+    #[allow(non_snake_case)]
+    pub fn compute_session_key_unchecked(
+        &self,
+        A: &PublicKey,
+        user_details: &UserDetails,
+        constants: &OpenConstants,
+    ) -> Result<StrongSessionKey> {
+        let S = calculate_session_key_S_for_host::<KEY_LENGTH>(
+            &constants.module,
+            A,
+            &self.B,
+            &self.b,
+            &user_details.verifier,
+            self.padding,
+        )?;
+
+        Ok(calculate_session_key_hash_interleave_K::<KEY_LENGTH>(
+            &S,
+            self.session_key_serialization,
+        ))
+    }
+
+    /// the [`PublicKey`] `B` a host with this configuration sends for the verifier `v`
+    /// and the private key `b`, e.g. for test vectors or to compare with a peer server
+    ///
+    /// formula: `B = k*v + g^b % N`, fails with [`Srp6Error::InvalidPublicKey`] if `B % N` is zero
+    pub fn compute_b(
+        &self,
+        verifier: &PasswordVerifier,
+        b: &ServerPrivateKey,
+        constants: &OpenConstants,
+    ) -> Result<PublicKey> {
+        let ephemeral = PrecomputedEphemeral::new(b.clone().into_inner(), constants);
+        let public_key = calculate_pubkey_B_from_g_b(
+            &constants.module,
+            &self.multiplier(constants),
+            verifier,
+            &ephemeral.g_b,
+        );
+        if (&public_key % &constants.module).is_zero() {
+            return Err(Srp6Error::InvalidPublicKey(public_key));
+        }
+
+        Ok(public_key)
+    }
+
+    /// rebuilds the host state from a persisted private key `b` after the
+    /// [`ServerHandshake`] was sent, `B` is recalculated from `b`
+    ///
+    /// fails with [`Srp6Error::InvalidPublicKey`] if `B % N` is zero
+    pub fn reconstruct(
+        mut self,
+        user_details: &UserDetails,
+        user_handshake: &UserHandshake,
+        constants: &OpenConstants,
+        b: ServerPrivateKey,
+    ) -> Result<Self> {
+        let handshake = self.handshake_with_ephemeral(
+            user_details,
+            user_handshake,
+            constants,
+            PrecomputedEphemeral::new(b.into_inner(), constants),
+        )?;
+        if (&handshake.server_publickey % &constants.module).is_zero() {
+            return Err(Srp6Error::InvalidPublicKey(handshake.server_publickey))
+                .context("while computing the server public key B");
+        }
+
+        Ok(self)
+    }
+
+    /// only checks the clients proof `M1`, e.g. for a gateway that just needs to know
+    /// the client has the password, without mutual authentication
+    ///
+    /// unlike [`HostAPI::verify_proof`] the strong proof `M2` is not calculated and
+    /// the host is not marked as verified, so [`Srp6::session_key`] stays `None`
+    pub fn verify_proof_only(&self, users_proof: &Proof) -> Result<()> {
+        self.ensure_verifiable()?;
+        if self.match_proof(users_proof).is_none() {
+            return Err(Srp6Error::InvalidProof(users_proof.clone()));
+        }
+
+        Ok(())
+    }
+
+    /// same as [`HostAPI::verify_proof`], but also returns the [`ProofLayout`] of the proof,
+    /// the configured one or one of [`Srp6::with_accepted_proof_layouts`]
+    pub fn verify_proof_with_layout(
+        &mut self,
+        users_proof: &Proof,
+    ) -> Result<(StrongProof, ProofLayout)> {
+        let verification = Verification::start(Side::Host);
+        let result = self.check_proof(users_proof);
+
+        verification.finish(result)
+    }
+
+    /// diagnostic for clients that hash the salt differently in `M`, e.g. unpadded:
+    /// whether `users_proof` matches `M` calculated over `client_salt` exactly as given,
+    /// instead of `PAD(s)` in little endian byte order, for the user of the handshake
+    ///
+    /// read only, the host is not marked as verified and no `M2` is calculated,
+    /// fails like [`HostAPI::verify_proof`] before [`HostAPI::continue_handshake`]
+    /// or for a too small `A`, the salt in `x` is not affected, if the client
+    /// differs there `v` does not match
+    #[allow(non_snake_case)]
+    pub fn verify_proof_with_salt(
+        &self,
+        users_proof: &Proof,
+        client_salt: &[u8],
+        constants: &OpenConstants,
+    ) -> Result<bool> {
+        self.ensure_verifiable()?;
+        let M = calculate_proof_M_with_salt_bytes::<KEY_LENGTH>(
+            &constants.module,
+            &constants.generator,
+            &self.username,
+            client_salt,
+            &self.A,
+            &self.B,
+            &self.K,
+            self.proof_layout,
+            self.padding,
+        );
+
+        Ok(proofs_match(&M, users_proof))
+    }
+
+    /// same as [`HostAPI::verify_proof`], but on failure checks whether the client
+    /// hashed the issued salt in another encoding into `M` (big endian or unpadded),
+    /// i.e. it knows the password, but has a bug, then [`Srp6Error::SaltMismatch`] is returned
+    ///
+    /// a different salt in `x` can not be told apart from a wrong password, as only `v` is known
+    #[cfg(feature = "diagnostics")]
+    #[allow(non_snake_case)]
+    pub fn verify_proof_diagnosed(
+        &mut self,
+        users_proof: &Proof,
+        I: UsernameRef,
+        constants: &OpenConstants,
+    ) -> Result<StrongProof> {
+        let error = match self.verify_proof(users_proof) {
+            Err(e @ Srp6Error::InvalidProof(_)) => e,
+            result => return result,
+        };
+        let mut padded_be = self.salt.to_array_pad_zero::<SALT_LENGTH>();
+        padded_be.reverse();
+        let encodings = [
+            padded_be.to_vec(),
+            self.salt.to_vec(),
+            self.salt.to_bytes_be(),
+        ];
+        for salt in encodings.iter() {
+            let M = calculate_proof_M_with_salt_bytes::<KEY_LENGTH>(
+                &constants.module,
+                &constants.generator,
+                I,
+                salt,
+                &self.A,
+                &self.B,
+                &self.K,
+                self.proof_layout,
+                self.padding,
+            );
+            if proofs_match(&M, users_proof) {
+                return Err(Srp6Error::SaltMismatch);
+            }
+        }
+
+        Err(error)
+    }
+
+    /// which [`IdentityHashing`] the stored `verifier` was generated with, tried with a
+    /// known (test) password and the default separator `":"`, or `None` if it matches no mode,
+    /// i.e. the password is wrong
+    ///
+    /// e.g. for admin tools when every login fails with [`Srp6Error::InvalidProof`]
+    /// after the identity hashing of the host was changed
+    #[cfg(feature = "diagnostics")]
+    #[allow(non_snake_case)]
+    pub fn diagnose_verifier(
+        I: UsernameRef,
+        known_password: &ClearTextPassword,
+        salt: &Salt,
+        verifier: &PasswordVerifier,
+        constants: &OpenConstants,
+    ) -> Option<IdentityHashing> {
+        [IdentityHashing::Rfc2945, IdentityHashing::PasswordOnly]
+            .iter()
+            .copied()
+            .find(|&mode| {
+                let x = calculate_private_key_x(
+                    mode,
+                    DEFAULT_IDENTITY_SEPARATOR,
+                    I,
+                    known_password,
+                    salt,
+                );
+                verifier.equals_g_pow_x(constants, &x)
+            })
+    }
+
+    /// draws `b` and calculates the expensive `g^b % N` ahead of time,
+    /// e.g. before a user connects, see [`Srp6::continue_handshake_with_ephemeral`]
+    pub fn precompute_ephemeral(constants: &OpenConstants) -> PrecomputedEphemeral {
+        PrecomputedEphemeral::new(generate_private_key::<KEY_LENGTH>(), constants)
+    }
+
+    /// same as [`HostAPI::continue_handshake`], but with `b` and `g^b % N` from
+    /// [`Srp6::precompute_ephemeral`], so only `B = k*v + g^b % N` is left to calculate
+    ///
+    /// **NOTE**: `constants` must be the ones the ephemeral was precomputed with
+    pub fn continue_handshake_with_ephemeral(
+        &mut self,
+        user_details: &UserDetails,
+        user_handshake: &UserHandshake,
+        constants: &OpenConstants,
+        ephemeral: PrecomputedEphemeral,
+    ) -> Result<ServerHandshake> {
+        telemetry::handshake_started(Side::Host);
+
+        self.handshake_with_ephemeral(user_details, user_handshake, constants, ephemeral)
+    }
+
+    /// [`Srp6::continue_handshake_with_ephemeral`] without the metrics, e.g. to [`Srp6::reconstruct`]
+    #[allow(non_snake_case)]
+    fn handshake_with_ephemeral(
+        &mut self,
+        user_details: &UserDetails,
+        user_handshake: &UserHandshake,
+        constants: &OpenConstants,
+        ephemeral: PrecomputedEphemeral,
+    ) -> Result<ServerHandshake> {
+        if user_details.username != user_handshake.username {
+            return Err(Srp6Error::UsernameMismatch);
+        }
+        ensure_group_length::<KEY_LENGTH>(constants).context("while checking the group")?;
+        Self::precheck_public_key(&user_handshake.user_publickey, constants)
+            .context("while checking the client public key A")?;
+        ensure_non_empty_salt::<SALT_LENGTH>(&user_details.salt)?;
+        ensure_max_length(&user_details.verifier, KEY_LENGTH)
+            .context("while checking the password verifier v")?;
+        if self.warn_on_weak_verifier && user_details.verifier.looks_weak(constants) {
+            warn!("a verifier looks weak, the account should be re-provisioned");
+        }
+        let B = calculate_pubkey_B_from_g_b(
+            &constants.module,
+            &self.multiplier(constants),
+            &user_details.verifier,
+            &ephemeral.g_b,
+        );
+
+        self.b = ephemeral.b;
+        self.group = constants.group_info();
+        self.username = user_details.username.clone();
+        self.B = B.clone();
+        self.A = user_handshake.user_publickey.clone();
+        self.U = calculate_u::<KEY_LENGTH>(&self.A, &self.B, self.padding);
+
+        self.S = calculate_session_key_S_for_host::<KEY_LENGTH>(
+            &constants.module,
+            &self.A,
+            &self.B,
+            &self.b,
+            &user_details.verifier,
+            self.padding,
+        )
+        .context("while computing the host session key S")?;
+        self.K = calculate_session_key_hash_interleave_K::<KEY_LENGTH>(
+            &self.S,
+            self.session_key_serialization,
+        );
+        self.M = calculate_proof_M::<KEY_LENGTH, SALT_LENGTH>(
+            &constants.module,
+            &constants.generator,
+            &user_details.username,
+            &user_details.salt,
+            &self.A,
+            &self.B,
+            &self.K,
+            self.proof_layout,
+            self.padding,
+        );
+        self.accepted_M = self
+            .accepted_proof_layouts
+            .iter()
+            .map(|layout| {
+                calculate_proof_M::<KEY_LENGTH, SALT_LENGTH>(
+                    &constants.module,
+                    &constants.generator,
+                    &user_details.username,
+                    &user_details.salt,
+                    &self.A,
+                    &self.B,
+                    &self.K,
+                    *layout,
+                    self.padding,
+                )
+            })
+            .collect();
+
+        Ok(ServerHandshake {
+            salt: user_details.salt.clone(),
+            server_publickey: B,
+        })
+    }
+}
+
+#[cfg(test)]
+thread_local! {
+    /// the secrets of the last host of this thread after they were wiped
+    static WIPED_SECRETS: std::cell::RefCell<Vec<crate::big_number::BigNumber>> = const { std::cell::RefCell::new(Vec::new()) };
+}
+
+/// overwrites the secrets with zeros, see [`Srp6::abort`]
+impl<const KEY_LENGTH: usize, const SALT_LENGTH: usize> Drop for Srp6<KEY_LENGTH, SALT_LENGTH> {
+    fn drop(&mut self) {
+        self.wipe_secrets();
+    }
+}
+
+/// the secrets `b`, `v`, `S`, `K` and the expected proof `M` are redacted
+impl<const KEY_LENGTH: usize, const SALT_LENGTH: usize> Debug for Srp6<KEY_LENGTH, SALT_LENGTH> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Srp6")
+            .field("A", &self.A)
+            .field("B", &self.B)
+            .field("b", &Redacted("PrivateKey"))
+            .field("U", &self.U)
+            .field("verifier", &Redacted("PasswordVerifier"))
+            .field("salt", &self.salt)
+            .field("username", &self.username)
+            .field("S", &Redacted("SessionKey"))
+            .field("K", &Redacted("StrongSessionKey"))
+            .field("M", &Redacted("Proof"))
+            .field("verified", &self.verified)
+            .field("multiplier", &self.multiplier)
+            .field("identity_hashing", &self.identity_hashing)
+            .field("identity_separator", &self.identity_separator)
+            .field("session_key_serialization", &self.session_key_serialization)
+            .field("proof_layout", &self.proof_layout)
+            .field("accepted_proof_layouts", &self.accepted_proof_layouts)
+            .field("padding", &self.padding)
+            .field("group", &self.group)
+            .field("min_public_key_bits", &self.min_public_key_bits)
+            .field("warn_on_weak_verifier", &self.warn_on_weak_verifier)
+            .finish()
+    }
+}
+
+/// e.g. `Srp6(2048-bit, SHA1, 256 byte salt)`, the alternate form `{:#}` adds the
+/// [`MultiplierParameter`] `k` and the [`IdentityHashing`]
+impl<const KEY_LENGTH: usize, const SALT_LENGTH: usize> Display for Srp6<KEY_LENGTH, SALT_LENGTH> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(
+            f,
+            "Srp6({}-bit, {}, {} byte salt",
+            KEY_LENGTH * 8,
+            HASH_NAME,
+            SALT_LENGTH
+        )?;
+        if f.alternate() {
+            match &self.multiplier {
+                Some(k) => write!(f, ", k={}", k)?,
+                None => write!(f, ", k=H(N | PAD(g))")?,
+            }
+            write!(f, ", {:?}", self.identity_hashing)?;
         }
+        write!(f, ")")
+    }
+}
+
+impl<const KEY_LENGTH: usize, const SALT_LENGTH: usize> Default for Srp6<KEY_LENGTH, SALT_LENGTH> {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
+impl UserCredentials<'_> {
+    /// registers the user in one call, see [`HostAPI::generate_new_user_secrets`]
+    ///
+    /// fails with [`Srp6Error::KeyLengthMismatch`] if `N` does not match `KEY_LENGTH`
+    pub fn into_user_details<const KEY_LENGTH: usize, const SALT_LENGTH: usize>(
+        self,
+        srp: &mut Srp6<KEY_LENGTH, SALT_LENGTH>,
+        constants: &OpenConstants,
+    ) -> Result<UserDetails> {
+        ensure_group_length::<KEY_LENGTH>(constants)?;
+
+        Ok(srp.generate_new_user_secrets(self.username, self.password, constants))
+    }
+}
 
 impl<const KEY_LENGTH: usize, const SALT_LENGTH: usize> HostAPI<KEY_LENGTH, SALT_LENGTH>
     for Srp6<KEY_LENGTH, SALT_LENGTH>
@@ -80,68 +856,1196 @@ impl<const KEY_LENGTH: usize, const SALT_LENGTH: usize> HostAPI<KEY_LENGTH, SALT
         p: &ClearTextPassword,
         constants: &OpenConstants,
     ) -> UserDetails {
-        self.salt = generate_salt::<SALT_LENGTH>();
-        // let s = BigNumber::from_hex_str_be("FFFFFFFFFFFFFFFFC90FDAA22168C234C4C6628B80DC1CD129024E088A67CC74020BBEA63B139B22514A08798E3404DDEF9519B3CD3A431B302B0A6DF25F14374FE1356D6D51C245E485B576625E7EC6F44C42E9A637ED6B0BFF5CB6F406B7EDEE386BFB5A899FA5AE9F24117C4B1FE649286651ECE45B3DC2007CB8A163BF0598DA48361C55D39A69163FA8FD24CF5F83655D23DCA3AD961C62F356208552BB9ED5290").unwrap();
-        let x = calculate_private_key_x(I, p, &self.salt);
-        self.verifier = calculate_password_verifier_v(&constants.module, &constants.generator, &x);
-        // self.salt = s.clone();
+        self.generate_new_user_secrets_with_rng(I, p, constants, &mut thread_rng())
+    }
 
-        UserDetails{
+    #[allow(non_snake_case)]
+    fn generate_new_user_secrets_with_rng<R: RngCore + CryptoRng>(
+        &mut self,
+        I: UsernameRef,
+        p: &ClearTextPassword,
+        constants: &OpenConstants,
+        rng: &mut R,
+    ) -> UserDetails {
+        self.salt = generate_salt_with_rng::<SALT_LENGTH, R>(rng);
+        self.verifier = compute_verifier(
+            self.identity_hashing,
+            &self.identity_separator,
+            &constants.module,
+            &constants.generator,
+            &self.salt,
+            I,
+            p,
+        );
+
+        UserDetails {
             username: I.to_owned(),
             salt: self.salt.clone(),
-            verifier: self.verifier.clone()
+            verifier: self.verifier.clone(),
         }
     }
 
+    #[allow(non_snake_case)]
+    fn generate_new_user_secrets_checked<P: Fn(&ClearTextPassword) -> bool>(
+        &mut self,
+        I: UsernameRef,
+        p: &ClearTextPassword,
+        constants: &OpenConstants,
+        policy: P,
+    ) -> Result<UserDetails> {
+        if !policy(p) {
+            return Err(Srp6Error::WeakPassword);
+        }
+
+        Ok(self.generate_new_user_secrets(I, p, constants))
+    }
+
+    #[allow(non_snake_case)]
+    fn generate_multi_group_secrets(
+        &mut self,
+        I: UsernameRef,
+        p: &ClearTextPassword,
+        groups: &[OpenConstants],
+    ) -> Vec<(GroupId, Salt, PasswordVerifier)> {
+        groups
+            .iter()
+            .map(|constants| {
+                let details = self.generate_new_user_secrets(I, p, constants);
+                (constants.group_id(), details.salt, details.verifier)
+            })
+            .collect()
+    }
+
     #[allow(non_snake_case)]
     fn continue_handshake(
-        &mut self, 
+        &mut self,
         user_details: &UserDetails,
         user_handshake: &UserHandshake,
-        constants: &OpenConstants
+        constants: &OpenConstants,
     ) -> Result<ServerHandshake> {
-
-        assert!(user_details.username == user_handshake.username, "wrong usernames");
         let b = generate_private_key::<KEY_LENGTH>();
-        debug!("b = {:?}", &b);
-
-        let B = calculate_pubkey_B(&constants.module, &constants.generator, &user_details.verifier, &b);
 
-        self.b = b;
-        self.B = B.clone();
-        self.A = user_handshake.user_publickey.clone();
-        self.U = calculate_u::<KEY_LENGTH>(&self.A, &self.B);
-        
-        self.S = calculate_session_key_S_for_host::<KEY_LENGTH>(&constants.module, &self.A, &self.B, &self.b, &self.verifier)?;
-        self.K = calculate_session_key_hash_interleave_K::<KEY_LENGTH>(&self.S);
-        self.M = calculate_proof_M::<KEY_LENGTH, SALT_LENGTH>(&constants.module, 
-            &constants.generator, &user_details.username, &user_details.salt, &self.A, &self.B, &self.K);
-
-        return Ok(ServerHandshake{
-            salt: user_details.salt.clone(),
-            server_publickey: B
-        });
+        self.continue_handshake_with_ephemeral(
+            user_details,
+            user_handshake,
+            constants,
+            PrecomputedEphemeral::new(b, constants),
+        )
     }
 
-    fn verify_proof(
+    fn continue_handshake_with_persist<F: FnMut(&Self)>(
         &mut self,
-        users_proof: &Proof
-    ) -> Result<Proof> {
+        user_details: &UserDetails,
+        user_handshake: &UserHandshake,
+        constants: &OpenConstants,
+        mut persist: F,
+    ) -> Result<ServerHandshake> {
+        let handshake = self.continue_handshake(user_details, user_handshake, constants)?;
+        persist(self);
 
+        Ok(handshake)
+    }
 
-        if self.M != *users_proof {
-            // println!("{} != {}", self.M, users_proof);
-            // println!("{:?}", self);
-            return Err(Srp6Error::InvalidProof(users_proof.clone()));
+    fn verify_proof(&mut self, users_proof: &Proof) -> Result<StrongProof> {
+        self.verify_proof_with_layout(users_proof)
+            .map(|(strong_proof, _)| strong_proof)
+    }
+
+    fn verify_proof_with_session(&mut self, users_proof: &Proof) -> Result<VerifiedSession> {
+        let strong_proof = self.verify_proof(users_proof)?;
+
+        Ok(VerifiedSession {
+            username: self.username.clone(),
+            strong_proof,
+            session_key: self.K.clone(),
+            client_public_key: self.A.clone(),
+            group: self.group,
+        })
+    }
+}
+
+impl<const KEY_LENGTH: usize, const SALT_LENGTH: usize> Srp6<KEY_LENGTH, SALT_LENGTH> {
+    /// the guards before a proof is checked against the handshake
+    fn ensure_verifiable(&self) -> Result<()> {
+        // also refuses to verify before `continue_handshake`
+        ensure_nonzero_u(&self.U)?;
+        if matches!(self.min_public_key_bits, Some(bits) if self.A.num_bits() < bits) {
+            return Err(Srp6Error::InvalidPublicKey(self.A.clone()));
         }
-        let hamk = calculate_strong_proof_M2::<KEY_LENGTH>(&self.A, &self.M, &self.K);
-        self.verified = true;
-        return Ok(hamk);
 
+        Ok(())
+    }
+
+    /// the expected `M` in the configured layout, then in each accepted one
+    fn expected_proofs(&self) -> impl Iterator<Item = (ProofLayout, &Proof)> {
+        std::iter::once((self.proof_layout, &self.M)).chain(
+            self.accepted_proof_layouts
+                .iter()
+                .copied()
+                .zip(self.accepted_M.iter()),
+        )
     }
 
+    /// the position in [`Self::expected_proofs`] of the first one `users_proof` matches,
+    /// every candidate is compared, so the time does not tell which one matched
+    fn match_proof(&self, users_proof: &Proof) -> Option<usize> {
+        self.expected_proofs()
+            .map(|(_, expected)| proofs_match(expected, users_proof))
+            .enumerate()
+            .fold(None, |found, (i, matches)| {
+                found.or(if matches { Some(i) } else { None })
+            })
+    }
+
+    /// [`Srp6::verify_proof_with_layout`] without the metrics
+    fn check_proof(&mut self, users_proof: &Proof) -> Result<(StrongProof, ProofLayout)> {
+        self.ensure_verifiable()?;
+        // M2 is calculated upfront, so the work done does not depend on the proof,
+        // it is over the `M1` of the client, so one per accepted layout
+        let mut hamks: Vec<(ProofLayout, StrongProof)> = self
+            .expected_proofs()
+            .map(|(layout, expected)| {
+                let hamk = calculate_strong_proof_M2::<KEY_LENGTH>(
+                    &self.A,
+                    expected,
+                    &self.K,
+                    self.padding,
+                );
+                (layout, hamk)
+            })
+            .collect();
+        let matched = self
+            .match_proof(users_proof)
+            .ok_or_else(|| Srp6Error::InvalidProof(users_proof.clone()))?;
+        let (layout, hamk) = hamks.swap_remove(matched);
+        self.verified = true;
+        Ok((hamk, layout))
+    }
+}
+
+/// pure version of [`HostAPI::generate_new_user_secrets`] for a given [`Salt`] `s`,
+/// e.g. for offline provisioning tools, the username is hashed as in [`IdentityHashing::Rfc2945`]
+#[allow(non_snake_case)]
+pub fn compute_user_secrets(
+    N: &PrimeModulus,
+    g: &Generator,
+    s: Salt,
+    I: UsernameRef,
+    p: &ClearTextPassword,
+) -> (Salt, PasswordVerifier) {
+    let v = compute_verifier(
+        IdentityHashing::default(),
+        DEFAULT_IDENTITY_SEPARATOR,
+        N,
+        g,
+        &s,
+        I,
+        p,
+    );
 
+    (s, v)
 }
 
+/// same as [`compute_user_secrets`], but with a fresh random [`Salt`] of `SALT_LENGTH` bytes
+#[allow(non_snake_case)]
+pub fn compute_user_secrets_with_random_salt<const SALT_LENGTH: usize>(
+    N: &PrimeModulus,
+    g: &Generator,
+    I: UsernameRef,
+    p: &ClearTextPassword,
+) -> (Salt, PasswordVerifier) {
+    let s = generate_salt_with_rng::<SALT_LENGTH, _>(&mut thread_rng());
+
+    compute_user_secrets(N, g, s, I, p)
+}
+
+#[allow(non_snake_case)]
+pub(crate) fn compute_verifier(
+    identity_hashing: IdentityHashing,
+    separator: &[u8],
+    N: &PrimeModulus,
+    g: &Generator,
+    s: &Salt,
+    I: UsernameRef,
+    p: &ClearTextPassword,
+) -> PasswordVerifier {
+    let x = calculate_private_key_x(identity_hashing, separator, I, p, s);
+
+    calculate_password_verifier_v(N, g, &x)
+}
+
+#[cfg(feature = "group-2048")]
+pub type Srp6_2048 = Srp6<256, 256>;
+#[cfg(feature = "group-4096")]
 pub type Srp6_4096 = Srp6<512, 512>;
 
+#[cfg(test)]
+#[cfg(feature = "group-1024")]
+mod tests {
+    use rand::rngs::StdRng;
+    use rand::SeedableRng;
+
+    use super::*;
+    use crate::big_number::BigNumber;
+    use crate::rfc5054_1024;
+
+    type Srp6_1024 = Srp6<128, 128>;
+
+    #[test]
+    fn should_generate_the_same_secrets_for_the_same_rng_seed() {
+        let constants = rfc5054_1024();
+        let a = Srp6_1024::new().generate_new_user_secrets_with_rng(
+            "Bob",
+            "secret-password",
+            &constants,
+            &mut StdRng::seed_from_u64(42),
+        );
+        let b = Srp6_1024::new().generate_new_user_secrets_with_rng(
+            "Bob",
+            "secret-password",
+            &constants,
+            &mut StdRng::seed_from_u64(42),
+        );
+
+        assert_eq!(a.salt, b.salt);
+        assert_eq!(a.verifier, b.verifier);
+    }
+
+    #[test]
+    fn should_draw_the_salt_from_the_given_rng() {
+        let constants = rfc5054_1024();
+        let a = Srp6_1024::new().generate_new_user_secrets_with_rng(
+            "Bob",
+            "secret-password",
+            &constants,
+            &mut StdRng::seed_from_u64(1),
+        );
+        let b = Srp6_1024::new().generate_new_user_secrets_with_rng(
+            "Bob",
+            "secret-password",
+            &constants,
+            &mut StdRng::seed_from_u64(2),
+        );
+
+        assert_ne!(a.salt, b.salt);
+        assert_ne!(a.verifier, b.verifier);
+    }
+
+    #[test]
+    fn should_refuse_passwords_failing_the_policy() {
+        let policy = |p: &ClearTextPassword| p.chars().count() >= 12;
+        let mut srp = Srp6_1024::new();
+
+        assert_eq!(
+            srp.generate_new_user_secrets_checked("Bob", "short", &rfc5054_1024(), policy)
+                .err(),
+            Some(Srp6Error::WeakPassword)
+        );
+        assert!(srp
+            .generate_new_user_secrets_checked(
+                "Bob",
+                "long-enough-password",
+                &rfc5054_1024(),
+                policy
+            )
+            .is_ok());
+    }
+
+    #[test]
+    fn should_generate_independent_secrets_per_group() {
+        let groups = [
+            rfc5054_1024(),
+            OpenConstants {
+                module: rfc5054_1024().module,
+                generator: Generator::from(5),
+            },
+        ];
+        let secrets =
+            Srp6_1024::new().generate_multi_group_secrets("Bob", "secret-password", &groups);
+
+        assert_eq!(secrets.len(), 2);
+        assert_eq!(secrets[0].0, groups[0].group_id());
+        assert_eq!(secrets[1].0, groups[1].group_id());
+        assert_ne!(secrets[0].1, secrets[1].1);
+        assert_ne!(secrets[0].2, secrets[1].2);
+    }
+
+    #[test]
+    fn should_register_user_credentials_in_one_call() {
+        let credentials = UserCredentials {
+            username: "Bob",
+            password: "secret-password",
+        };
+        let details = credentials
+            .clone()
+            .into_user_details(&mut Srp6_1024::new(), &rfc5054_1024())
+            .unwrap();
+        assert_eq!(details.username, "Bob");
+
+        assert_eq!(
+            credentials
+                .into_user_details(
+                    &mut Srp6_1024::new(),
+                    &OpenConstants {
+                        module: PrimeModulus::from(23),
+                        generator: Generator::from(5),
+                    },
+                )
+                .err(),
+            Some(Srp6Error::KeyLengthMismatch {
+                given: 1,
+                expected: 128
+            })
+        );
+    }
+
+    #[test]
+    fn should_persist_the_host_state_before_returning_the_handshake() {
+        let constants = rfc5054_1024();
+        let mut host = Srp6_1024::new();
+        let details = host.generate_new_user_secrets("Bob", "secret-password", &constants);
+        let user_handshake = UserHandshake {
+            username: "Bob".to_owned(),
+            user_publickey: PublicKey::from(5),
+        };
+
+        let mut persisted = None;
+        let handshake = host
+            .continue_handshake_with_persist(&details, &user_handshake, &constants, |host| {
+                persisted = Some(host.B.clone())
+            })
+            .unwrap();
+        assert_eq!(persisted, Some(handshake.server_publickey));
+    }
+
+    #[test]
+    fn should_display_the_configuration_without_secrets() {
+        assert_eq!(
+            Srp6_1024::new().to_string(),
+            "Srp6(1024-bit, SHA1, 128 byte salt)"
+        );
+        assert_eq!(
+            format!("{:#}", Srp6_1024::new()),
+            "Srp6(1024-bit, SHA1, 128 byte salt, k=H(N | PAD(g)), Rfc2945)"
+        );
+        assert_eq!(
+            format!(
+                "{:#}",
+                Srp6_1024::new().with_multiplier(MultiplierParameter::from(3))
+            ),
+            "Srp6(1024-bit, SHA1, 128 byte salt, k=3, Rfc2945)"
+        );
+    }
+
+    #[test]
+    fn should_authenticate_with_a_precomputed_ephemeral() {
+        use crate::{Srp6User, UserTrait};
+
+        let constants = rfc5054_1024();
+        let ephemeral = Srp6_1024::precompute_ephemeral(&constants);
+        let b = ephemeral.b.clone();
+
+        let mut host = Srp6_1024::new();
+        let mut user = Srp6User::<128, 128>::new();
+        let details = host.generate_new_user_secrets("Bob", "secret-password", &constants);
+        let user_handshake = user.start_handshake("Bob", &constants);
+        let server_handshake = host
+            .continue_handshake_with_ephemeral(&details, &user_handshake, &constants, ephemeral)
+            .unwrap();
+        assert_eq!(host.private_key().into_inner(), b);
+
+        let proof = user
+            .update_handshake(&server_handshake, &constants, "Bob", "secret-password")
+            .unwrap();
+        let strong_proof = host.verify_proof(&proof).unwrap();
+        assert!(user.verify_proof(&strong_proof).is_ok());
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn should_precheck_the_clients_public_key() {
+        let constants = rfc5054_1024();
+        let N = &constants.module;
+        assert!(Srp6_1024::precheck_public_key(&PublicKey::from(5), &constants).is_ok());
+        assert!(Srp6_1024::precheck_public_key(&(N - &PublicKey::from(1)), &constants).is_ok());
+
+        for A in [PublicKey::from(0), N.clone(), N + &PublicKey::from(5)].iter() {
+            assert_eq!(
+                Srp6_1024::precheck_public_key(A, &constants),
+                Err(Srp6Error::InvalidPublicKey(A.clone()))
+            );
+        }
+    }
+
+    #[test]
+    fn should_compute_b_offline() {
+        let constants = rfc5054_1024();
+        let mut host = Srp6_1024::new();
+        let details = host.generate_new_user_secrets("Bob", "secret-password", &constants);
+        let user_handshake = UserHandshake {
+            username: "Bob".to_owned(),
+            user_publickey: PublicKey::from(5),
+        };
+        let server_handshake = host
+            .continue_handshake(&details, &user_handshake, &constants)
+            .unwrap();
+        let b = host.private_key();
+
+        assert_eq!(
+            Srp6_1024::new().compute_b(&details.verifier, &b, &constants),
+            Ok(server_handshake.server_publickey)
+        );
+        assert_ne!(
+            Srp6_1024::new()
+                .with_multiplier(MultiplierParameter::from(3))
+                .compute_b(&details.verifier, &b, &constants),
+            Srp6_1024::new().compute_b(&details.verifier, &b, &constants)
+        );
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn should_compute_the_b_the_client_authenticates_with() {
+        use crate::{Srp6User, UserTrait};
+
+        let constants = rfc5054_1024();
+        let details =
+            Srp6_1024::new().generate_new_user_secrets("Bob", "secret-password", &constants);
+        let b = ServerPrivateKey::from(PrivateKey::from_hex_str_be("C0FFEE").unwrap());
+        let mut user = Srp6User::<128, 128>::new();
+        let user_handshake = user
+            .start_handshake_with_private_key(
+                "Bob",
+                &constants,
+                ClientPrivateKey::from(PrivateKey::from_hex_str_be("BADC0DE").unwrap()),
+            )
+            .unwrap();
+
+        let B = Srp6_1024::new()
+            .compute_b(&details.verifier, &b, &constants)
+            .unwrap();
+        let mut host = Srp6_1024::new()
+            .reconstruct(&details, &user_handshake, &constants, b)
+            .unwrap();
+        assert_eq!(host.B, B);
+        let server_handshake = ServerHandshake {
+            salt: details.salt.clone(),
+            server_publickey: B,
+        };
+        let proof = user
+            .update_handshake(&server_handshake, &constants, "Bob", "secret-password")
+            .unwrap();
+        let strong_proof = host.verify_proof(&proof).unwrap();
+        assert!(user.verify_proof(&strong_proof).is_ok());
+    }
+
+    #[test]
+    fn should_refuse_public_keys_with_too_few_bits() {
+        use crate::{Srp6User, UserTrait};
+
+        let constants = rfc5054_1024();
+        let details =
+            Srp6_1024::new().generate_new_user_secrets("Bob", "secret-password", &constants);
+        let handshake = |user_publickey: PublicKey| {
+            let mut host = Srp6_1024::new().with_min_public_key_bits(512);
+            let user_handshake = UserHandshake {
+                username: "Bob".to_owned(),
+                user_publickey,
+            };
+            host.continue_handshake(&details, &user_handshake, &constants)
+                .unwrap();
+            host
+        };
+
+        for weak_key in [PublicKey::from(1), constants.generator.clone()].iter() {
+            let refused = Err(Srp6Error::InvalidPublicKey(weak_key.clone()));
+            let mut host = handshake(weak_key.clone());
+            assert_eq!(host.verify_proof_only(&Proof::from(7)), refused);
+            assert_eq!(
+                host.verify_proof_with_salt(&Proof::from(7), &details.salt.to_vec(), &constants)
+                    .map(|_| ()),
+                refused
+            );
+            assert_eq!(host.verify_proof(&Proof::from(7)).map(|_| ()), refused);
+        }
+
+        let mut user = Srp6User::<128, 128>::new();
+        let user_handshake = user.start_handshake("Bob", &constants);
+        let mut host = handshake(user_handshake.user_publickey);
+        let server_handshake = ServerHandshake {
+            salt: details.salt.clone(),
+            server_publickey: host.B.clone(),
+        };
+        let proof = user
+            .update_handshake(&server_handshake, &constants, "Bob", "secret-password")
+            .unwrap();
+        assert!(host.verify_proof(&proof).is_ok());
+    }
+
+    #[test]
+    fn should_check_a_verifier_against_the_private_key() {
+        let constants = rfc5054_1024();
+        let details =
+            Srp6_1024::new().generate_new_user_secrets("Bob", "secret-password", &constants);
+        let x = |p| {
+            calculate_private_key_x(
+                IdentityHashing::default(),
+                DEFAULT_IDENTITY_SEPARATOR,
+                "Bob",
+                p,
+                &details.salt,
+            )
+        };
+
+        assert!(details
+            .verifier
+            .equals_g_pow_x(&constants, &x("secret-password")));
+        assert!(!details
+            .verifier
+            .equals_g_pow_x(&constants, &x("wrong-password")));
+        // congruent, but not reduced
+        let unreduced = &details.verifier + &constants.module;
+        assert!(!unreduced.equals_g_pow_x(&constants, &x("secret-password")));
+    }
+
+    #[test]
+    fn should_refuse_a_zero_u() {
+        assert_eq!(
+            ensure_nonzero_u(&BigNumber::from(0)),
+            Err(Srp6Error::ZeroScalar)
+        );
+        assert!(ensure_nonzero_u(&BigNumber::from(1)).is_ok());
+
+        // no handshake happened, so `u` is still zero
+        assert_eq!(
+            Srp6_1024::new().verify_proof(&Proof::from(7)),
+            Err(Srp6Error::ZeroScalar)
+        );
+    }
+
+    #[test]
+    fn should_redact_secrets_in_debug_output() {
+        let constants = rfc5054_1024();
+        let details =
+            Srp6_1024::new().generate_new_user_secrets("Bob", "secret-password", &constants);
+        let user_handshake = UserHandshake {
+            username: "Bob".to_owned(),
+            user_publickey: constants
+                .generator
+                .modpow(&BigNumber::from(7), &constants.module),
+        };
+        let mut srp6 = Srp6_1024::new();
+        srp6.continue_handshake(&details, &user_handshake, &constants)
+            .unwrap();
+
+        let debug = format!("{:?}", srp6);
+        assert!(debug.contains("b: PrivateKey(<redacted>)"));
+        assert!(debug.contains("K: StrongSessionKey(<redacted>)"));
+        assert!(!debug.contains(&srp6.b.to_string()));
+        assert!(!debug.contains(&details.verifier.to_string()));
+        assert!(!debug.contains(&srp6.S.to_string()));
+        assert!(!debug.contains(&srp6.K.to_string()));
+        assert!(debug.contains(&srp6.B.to_string()));
+
+        let debug = format!("{:?}", details);
+        assert!(!debug.contains(&details.verifier.to_string()));
+        assert_eq!(
+            format!("{:?}", srp6.private_key()),
+            "ServerPrivateKey(<redacted>)"
+        );
+    }
+
+    #[test]
+    fn should_not_serialize_secrets() {
+        use crate::{Srp6User, UserTrait};
+
+        let constants = rfc5054_1024();
+        let mut host = Srp6_1024::new();
+        let mut user = Srp6User::<128, 128>::new();
+        let details = host.generate_new_user_secrets("Bob", "secret-password", &constants);
+        let user_handshake = user.start_handshake("Bob", &constants);
+        let server_handshake = host
+            .continue_handshake(&details, &user_handshake, &constants)
+            .unwrap();
+        user.update_handshake(&server_handshake, &constants, "Bob", "secret-password")
+            .unwrap();
+
+        let json = serde_json::to_value(&host).unwrap();
+        let fields = json.as_object().unwrap();
+        assert!(fields.contains_key("B"));
+        for secret in ["b", "verifier", "S", "K", "M", "accepted_M"] {
+            assert!(!fields.contains_key(secret), "{}", secret);
+        }
+        let json = serde_json::to_value(&user).unwrap();
+        let fields = json.as_object().unwrap();
+        assert!(fields.contains_key("A"));
+        for secret in ["a", "S", "K"] {
+            assert!(!fields.contains_key(secret), "{}", secret);
+        }
+    }
+
+    #[test]
+    fn should_verify_a_proof_over_the_unpadded_salt() {
+        let constants = rfc5054_1024();
+        let mut host = Srp6_1024::new();
+        let details = host.generate_new_user_secrets("Bob", "secret-password", &constants);
+        let user_handshake = UserHandshake {
+            username: "Bob".to_owned(),
+            user_publickey: PublicKey::from(5),
+        };
+        host.continue_handshake(&details, &user_handshake, &constants)
+            .unwrap();
+
+        // a client hashing the salt in big endian byte order
+        let salt = details.salt.to_bytes_be();
+        let proof = calculate_proof_M_with_salt_bytes::<128>(
+            &constants.module,
+            &constants.generator,
+            "Bob",
+            &salt,
+            &host.A,
+            &host.B,
+            &host.K,
+            ProofLayout::default(),
+            PaddingPolicy::default(),
+        );
+        assert!(host.verify_proof(&proof).is_err());
+        assert_eq!(
+            host.verify_proof_with_salt(&proof, &salt, &constants),
+            Ok(true)
+        );
+        assert_eq!(
+            host.verify_proof_with_salt(&Proof::from(7), &salt, &constants),
+            Ok(false)
+        );
+        // only a diagnostic, the host is not verified
+        assert!(host.session_key().is_none());
+        assert!(host.verify_proof(&proof).is_err());
+    }
+
+    #[test]
+    fn should_not_verify_a_proof_over_the_salt_before_the_handshake() {
+        let constants = rfc5054_1024();
+        let host = Srp6_1024::new();
+        // A, B and K are all zero, so anyone could calculate this M
+        let proof = calculate_proof_M_with_salt_bytes::<128>(
+            &constants.module,
+            &constants.generator,
+            "",
+            &[1],
+            &host.A,
+            &host.B,
+            &host.K,
+            ProofLayout::default(),
+            PaddingPolicy::default(),
+        );
+
+        assert_eq!(
+            host.verify_proof_with_salt(&proof, &[1], &constants),
+            Err(Srp6Error::ZeroScalar)
+        );
+        assert!(host.session_key().is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "testing")]
+    fn should_generate_the_same_secrets_for_a_seed() {
+        let constants = rfc5054_1024();
+        let details = Srp6_1024::new().generate_new_user_secrets_seeded(
+            "Bob",
+            "secret-password",
+            &constants,
+            42,
+        );
+        assert_eq!(details.salt, Salt::from_seed(42, 128));
+        assert_eq!(
+            details.verifier.to_string(),
+            "678B6D28D0F3450B3D2AF5647C10F74F095D92F240BA8DCDA349DDF3B1CB16ED64E68B14F0B81D09D4CFF5629D0A32C7A3A133C7E477A42FD5FB3A4A4D6000E51851FA3ACDEF898E823F703DF2D1E0372B8CB06CD3924BBEC3A07981816DC24084EB2583557FCC2CCEE8D20D47BD4320979BB1DC4D0CB94DCF58BC485665F185"
+        );
+
+        let other = Srp6_1024::new().generate_new_user_secrets_seeded(
+            "Bob",
+            "secret-password",
+            &constants,
+            43,
+        );
+        assert_ne!(details.verifier, other.verifier);
+    }
+
+    #[test]
+    fn should_round_trip_user_details_through_json() {
+        let constants = rfc5054_1024();
+        let details =
+            Srp6_1024::new().generate_new_user_secrets("Bob", "secret-password", &constants);
+
+        let json = serde_json::to_string(&details).unwrap();
+        assert!(json.contains(&format!("\"verifier\":\"{}\"", details.verifier)));
+        let restored: UserDetails = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.username, details.username);
+        assert_eq!(restored.salt, details.salt);
+        assert_eq!(restored.verifier, details.verifier);
+        assert!(Srp6_1024::validate_user(&restored).is_ok());
+
+        for broken in [
+            r#"{"username":"Bob","salt":"not hex","verifier":"0A"}"#,
+            r#"{"username":"Bob","salt":"0A","verifier":"00"}"#,
+            r#"{"username":"Bob","salt":"0A"}"#,
+        ]
+        .iter()
+        .copied()
+        {
+            assert!(serde_json::from_str::<UserDetails>(broken).is_err());
+        }
+
+        let huge = format!(
+            r#"{{"username":"Bob","salt":"0A","verifier":"{}"}}"#,
+            "F".repeat(10 * 1024 * 1024)
+        );
+        let error = serde_json::from_str::<UserDetails>(&huge).unwrap_err();
+        assert!(
+            error.to_string().contains("longer than expected"),
+            "{}",
+            error
+        );
+    }
+
+    #[test]
+    fn should_frame_user_details_for_the_registration_upload() {
+        let constants = rfc5054_1024();
+        let details =
+            Srp6_1024::new().generate_new_user_secrets("Bob", "secret-password", &constants);
+
+        let bytes = details.to_registration_bytes::<128, 128>().unwrap();
+        assert_eq!(bytes.len(), UserDetails::registration_len::<128, 128>(3));
+        assert_eq!(
+            UserDetails::registration_len_of(&bytes[..10]),
+            Some(bytes.len())
+        );
+        assert_eq!(UserDetails::registration_len_of(&bytes[..3]), None);
+        let restored = UserDetails::from_registration_bytes::<128, 128>(&bytes).unwrap();
+        assert_eq!(restored.username, details.username);
+        assert_eq!(restored.salt, details.salt);
+        assert_eq!(restored.verifier, details.verifier);
+
+        // a partial upload tells how much is expected, so it can be resumed
+        assert_eq!(
+            UserDetails::from_registration_bytes::<128, 128>(&bytes[..100]).err(),
+            Some(Srp6Error::KeyLengthMismatch {
+                given: 100,
+                expected: bytes.len()
+            })
+        );
+        let mut corrupted = bytes.clone();
+        corrupted[50] ^= 1;
+        assert_eq!(
+            UserDetails::from_registration_bytes::<128, 128>(&corrupted).err(),
+            Some(Srp6Error::InvalidChecksum)
+        );
+        assert!(matches!(
+            UserDetails::from_registration_bytes::<256, 128>(&bytes),
+            Err(Srp6Error::KeyLengthMismatch { .. })
+        ));
+        assert!(matches!(
+            details.to_registration_bytes::<64, 128>(),
+            Err(Srp6Error::KeyLengthMismatch { .. })
+        ));
+    }
+
+    #[test]
+    fn should_flag_weak_verifiers() {
+        let constants = rfc5054_1024();
+        let details =
+            Srp6_1024::new().generate_new_user_secrets("Bob", "secret-password", &constants);
+
+        assert!(!details.verifier.looks_weak(&constants));
+        assert!(PasswordVerifier::from(1).looks_weak(&constants));
+        assert!(constants.generator.looks_weak(&constants));
+        assert!(PasswordVerifier::from(u32::MAX).looks_weak(&constants));
+        let with_v_as_generator = OpenConstants {
+            module: constants.module.clone(),
+            generator: details.verifier.clone(),
+        };
+        assert!(details.verifier.looks_weak(&with_v_as_generator));
+
+        // only a warning, if enabled, the handshake goes on
+        let weak = UserDetails {
+            verifier: PasswordVerifier::from(1),
+            ..details
+        };
+        let user_handshake = UserHandshake {
+            username: "Bob".to_owned(),
+            user_publickey: PublicKey::from(5),
+        };
+        let mut host = Srp6_1024::new().with_weak_verifier_warning(true);
+        assert!(format!("{:?}", host).contains("warn_on_weak_verifier: true"));
+        assert!(host
+            .continue_handshake(&weak, &user_handshake, &constants)
+            .is_ok());
+    }
+
+    #[test]
+    fn should_check_the_verifier_length_on_first_use() {
+        let constants = rfc5054_1024();
+        let json = format!(
+            r#"{{"username":"Bob","salt":"0A","verifier":"{}"}}"#,
+            "AB".repeat(129)
+        );
+        let details: UserDetails = serde_json::from_str(&json).unwrap();
+        let user_handshake = UserHandshake {
+            username: "Bob".to_owned(),
+            user_publickey: PublicKey::from(5),
+        };
+
+        assert_eq!(
+            Srp6_1024::validate_user(&details),
+            Err(Srp6Error::InvalidUserDetails("verifier"))
+        );
+        let error = Srp6_1024::new()
+            .continue_handshake(&details, &user_handshake, &constants)
+            .unwrap_err();
+        assert_eq!(
+            error.root_cause(),
+            &Srp6Error::KeyLengthMismatch {
+                given: 129,
+                expected: 128
+            }
+        );
+    }
+
+    #[test]
+    fn should_redact_the_verifier_of_user_details() {
+        let constants = rfc5054_1024();
+        let details =
+            Srp6_1024::new().generate_new_user_secrets("Bob", "secret-password", &constants);
+
+        let debug = format!("{:?}", details);
+        assert!(debug.contains("Bob"));
+        assert!(!debug.contains(&details.verifier.to_string()));
+    }
+
+    #[test]
+    #[cfg(feature = "testing")]
+    #[allow(non_snake_case)]
+    fn should_reject_a_proof_of_a_mismatching_session_key() {
+        let constants = rfc5054_1024();
+        let mut host = Srp6_1024::new();
+        let details = host.generate_new_user_secrets("Bob", "secret-password", &constants);
+        let user_handshake = UserHandshake {
+            username: "Bob".to_owned(),
+            user_publickey: PublicKey::from(5),
+        };
+        host.continue_handshake(&details, &user_handshake, &constants)
+            .unwrap();
+
+        let K = host
+            .compute_session_key_unchecked(&host.A, &details, &constants)
+            .unwrap();
+        assert_eq!(K, host.K);
+
+        let other_A = PublicKey::from(7);
+        let other_K = host
+            .compute_session_key_unchecked(&other_A, &details, &constants)
+            .unwrap();
+        assert_ne!(other_K, host.K);
+        let proof = calculate_proof_M::<128, 128>(
+            &constants.module,
+            &constants.generator,
+            "Bob",
+            &details.salt,
+            &host.A,
+            &host.B,
+            &other_K,
+            ProofLayout::default(),
+            PaddingPolicy::default(),
+        );
+        assert!(matches!(
+            host.verify_proof(&proof),
+            Err(Srp6Error::InvalidProof(_))
+        ));
+        assert!(matches!(
+            host.compute_session_key_unchecked(&constants.module, &details, &constants),
+            Err(Srp6Error::InvalidPublicKey(_))
+        ));
+    }
+
+    #[test]
+    #[cfg(feature = "diagnostics")]
+    fn should_diagnose_a_salt_mismatch() {
+        let constants = rfc5054_1024();
+        let mut host = Srp6_1024::new();
+        let details = host.generate_new_user_secrets("Bob", "secret-password", &constants);
+        let user_handshake = UserHandshake {
+            username: "Bob".to_owned(),
+            user_publickey: PublicKey::from(5),
+        };
+        host.continue_handshake(&details, &user_handshake, &constants)
+            .unwrap();
+
+        let proof = calculate_proof_M_with_salt_bytes::<128>(
+            &constants.module,
+            &constants.generator,
+            "Bob",
+            &details.salt.to_bytes_be(),
+            &host.A,
+            &host.B,
+            &host.K,
+            ProofLayout::default(),
+            PaddingPolicy::default(),
+        );
+        assert_eq!(
+            host.verify_proof_diagnosed(&proof, "Bob", &constants),
+            Err(Srp6Error::SaltMismatch)
+        );
+        assert!(matches!(
+            host.verify_proof_diagnosed(&Proof::from(7), "Bob", &constants),
+            Err(Srp6Error::InvalidProof(_))
+        ));
+        assert!(host.session_key().is_none());
+    }
+
+    #[test]
+    fn should_wipe_the_secrets_on_abort() {
+        use crate::{Srp6User, UserTrait};
+
+        let constants = rfc5054_1024();
+        for abort in [Srp6_1024::abort, std::mem::drop] {
+            let mut host = Srp6_1024::new();
+            let mut user = Srp6User::<128, 128>::new();
+            let details = host.generate_new_user_secrets("Bob", "secret-password", &constants);
+            let user_handshake = user.start_handshake("Bob", &constants);
+            host.continue_handshake(&details, &user_handshake, &constants)
+                .unwrap();
+            assert!(!host.b.is_zero() && !host.K.is_zero());
+            WIPED_SECRETS.with(|wiped| wiped.borrow_mut().clear());
+
+            abort(host);
+            WIPED_SECRETS.with(|wiped| {
+                let wiped = wiped.borrow();
+                assert_eq!(wiped.len(), 5);
+                assert!(wiped.iter().all(Zero::is_zero));
+            });
+        }
+    }
+
+    #[test]
+    fn should_require_a_security_level() {
+        assert_eq!(Srp6_1024::security_level(), 80);
+        assert!(Srp6_1024::new().require_security_level(80).is_ok());
+        assert_eq!(
+            Srp6_1024::new().require_security_level(112).err(),
+            Some(Srp6Error::WeakGroup {
+                security_level: 80,
+                required: 112
+            })
+        );
+        #[cfg(feature = "group-2048")]
+        assert!(crate::Srp6_2048::new().require_security_level(112).is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "diagnostics")]
+    fn should_diagnose_the_identity_hashing_of_a_verifier() {
+        let constants = rfc5054_1024();
+        for mode in [IdentityHashing::Rfc2945, IdentityHashing::PasswordOnly] {
+            let details = Srp6_1024::new()
+                .with_identity_hashing(mode)
+                .generate_new_user_secrets("Bob", "secret-password", &constants);
+
+            assert_eq!(
+                Srp6_1024::diagnose_verifier(
+                    "Bob",
+                    "secret-password",
+                    &details.salt,
+                    &details.verifier,
+                    &constants
+                ),
+                Some(mode)
+            );
+            assert_eq!(
+                Srp6_1024::diagnose_verifier(
+                    "Bob",
+                    "wrong-password",
+                    &details.salt,
+                    &details.verifier,
+                    &constants
+                ),
+                None
+            );
+        }
+    }
+
+    #[test]
+    fn should_compute_the_same_secrets_as_the_host() {
+        let constants = rfc5054_1024();
+        let details =
+            Srp6_1024::new().generate_new_user_secrets("Bob", "secret-password", &constants);
+
+        let (salt, verifier) = compute_user_secrets(
+            &constants.module,
+            &constants.generator,
+            details.salt.clone(),
+            "Bob",
+            "secret-password",
+        );
+        assert_eq!(salt, details.salt);
+        assert_eq!(verifier, details.verifier);
+
+        let (salt, verifier) = compute_user_secrets_with_random_salt::<128>(
+            &constants.module,
+            &constants.generator,
+            "Bob",
+            "secret-password",
+        );
+        assert_ne!(salt, details.salt);
+        assert_ne!(verifier, details.verifier);
+    }
+
+    #[test]
+    fn should_attach_the_failed_operation_to_the_error() {
+        use std::error::Error;
+
+        let constants = rfc5054_1024();
+        let details =
+            Srp6_1024::new().generate_new_user_secrets("Bob", "secret-password", &constants);
+        let user_handshake = UserHandshake {
+            username: "Bob".to_owned(),
+            user_publickey: constants.module.clone(),
+        };
+
+        let error = Srp6_1024::new()
+            .continue_handshake(&details, &user_handshake, &constants)
+            .unwrap_err();
+        assert_eq!(error.to_string(), "while checking the client public key A");
+        assert_eq!(
+            error.source().unwrap().to_string(),
+            "The provided public key is invalid"
+        );
+        assert_eq!(
+            error.root_cause(),
+            &Srp6Error::InvalidPublicKey(constants.module)
+        );
+    }
+
+    #[test]
+    fn should_validate_stored_user_details() {
+        let details =
+            Srp6_1024::new().generate_new_user_secrets("Bob", "secret-password", &rfc5054_1024());
+        assert!(Srp6_1024::validate_user(&details).is_ok());
+        // empty usernames authenticate, so their records are valid
+        let nameless =
+            Srp6_1024::new().generate_new_user_secrets("", "secret-password", &rfc5054_1024());
+        assert!(Srp6_1024::validate_user(&nameless).is_ok());
+
+        for (field, broken) in [
+            (
+                "salt",
+                UserDetails {
+                    salt: Salt::from([0x42; 129]),
+                    ..details.clone()
+                },
+            ),
+            (
+                "verifier",
+                UserDetails {
+                    verifier: PasswordVerifier::default(),
+                    ..details.clone()
+                },
+            ),
+            (
+                "verifier",
+                UserDetails {
+                    verifier: PasswordVerifier::from([0x42; 129]),
+                    ..details.clone()
+                },
+            ),
+        ] {
+            assert_eq!(
+                Srp6_1024::validate_user(&broken),
+                Err(Srp6Error::InvalidUserDetails(field))
+            );
+        }
+    }
+
+    #[test]
+    fn should_compare_handshakes() {
+        let constants = rfc5054_1024();
+        let details =
+            Srp6_1024::new().generate_new_user_secrets("Bob", "secret-password", &constants);
+        let user_handshake = UserHandshake {
+            username: "Bob".to_owned(),
+            user_publickey: PublicKey::from(5),
+        };
+
+        let handshake = Srp6_1024::new()
+            .continue_handshake(&details, &user_handshake, &constants)
+            .unwrap();
+        let reissued = ServerHandshake::new::<128, 128>(
+            handshake.salt().clone(),
+            handshake.server_publickey().clone(),
+        )
+        .unwrap();
+        assert_eq!(handshake, reissued);
+
+        let other = Srp6_1024::new()
+            .continue_handshake(&details, &user_handshake, &constants)
+            .unwrap();
+        assert_eq!(handshake.salt, other.salt);
+        assert_ne!(handshake, other);
+    }
+
+    #[test]
+    fn should_validate_the_length_of_a_received_user_handshake() {
+        let handshake = UserHandshake::new::<128>("Bob".to_owned(), PublicKey::from([1; 128]));
+        assert_eq!(handshake.unwrap().username(), "Bob");
+
+        assert_eq!(
+            UserHandshake::new::<128>("Bob".to_owned(), PublicKey::from([1; 129])).err(),
+            Some(Srp6Error::KeyLengthMismatch {
+                given: 129,
+                expected: 128
+            })
+        );
+    }
+
+    #[test]
+    fn should_validate_the_length_of_a_received_server_handshake() {
+        let handshake = ServerHandshake::new::<128, 16>(Salt::from([1; 16]), PublicKey::from(5));
+        assert_eq!(handshake.unwrap().salt(), &Salt::from([1; 16]));
+
+        assert_eq!(
+            ServerHandshake::new::<128, 16>(Salt::from([1; 17]), PublicKey::from(5)).err(),
+            Some(Srp6Error::KeyLengthMismatch {
+                given: 17,
+                expected: 16
+            })
+        );
+    }
+
+    #[test]
+    fn should_accept_a_generated_salt() {
+        let details =
+            Srp6_1024::new().generate_new_user_secrets("Bob", "secret-password", &rfc5054_1024());
+        assert!(Srp6_1024::validate_salt(&details.salt).is_ok());
+    }
+
+    #[test]
+    fn should_reject_weak_salts() {
+        for salt in [Salt::default(), Salt::from([0xFF_u8; 128])] {
+            assert_eq!(
+                Srp6_1024::validate_salt(&salt),
+                Err(Srp6Error::WeakSalt(salt))
+            );
+        }
+    }
+
+    #[test]
+    fn should_reject_too_long_salts() {
+        assert_eq!(
+            Srp6_1024::validate_salt(&Salt::from([0x42_u8; 129])),
+            Err(Srp6Error::KeyLengthMismatch {
+                given: 129,
+                expected: 128
+            })
+        );
+    }
+}
@@ -1,47 +1,71 @@
 // use super::host::Handshake;
+use crate::big_number::Zero;
 use crate::primitives::*;
-use crate::{Result};
+use crate::telemetry::{self, Side, Verification};
+use crate::{Context, Result, Srp6Error};
 use serde::Serialize;
-
-use log::debug;
-
+use std::fmt::{Debug, Formatter};
 
 pub trait UserTrait<const KL: usize, const SL: usize> {
-
     #[allow(non_snake_case)]
     fn start_handshake(
         &mut self,
         username: UsernameRef,
-        constants: &OpenConstants
+        constants: &OpenConstants,
     ) -> UserHandshake;
 
     #[allow(non_snake_case)]
     fn update_handshake(
-        &mut self, 
+        &mut self,
         server_handshake: &ServerHandshake,
         constants: &OpenConstants,
         I: UsernameRef,
         p: &ClearTextPassword,
     ) -> Result<Proof>;
 
+    /// same as [`UserTrait::update_handshake`], for protocols that send the salt separately
+    ///
+    /// **NOTE**: `salt` must be the one the server has for this user (e.g. from a prior message),
+    /// otherwise the proof will fail
+    #[allow(non_snake_case)]
+    fn update_handshake_without_salt(
+        &mut self,
+        server_handshake: &ServerHandshakeNoSalt,
+        salt: &Salt,
+        constants: &OpenConstants,
+        I: UsernameRef,
+        p: &ClearTextPassword,
+    ) -> Result<Proof>;
+
+    /// verifies the servers [`StrongProof`] `M2`,
+    /// on success the verified `M2` and the [`StrongSessionKey`] `K` are returned
     fn verify_proof(
         &mut self,
-        servers_proof: &Proof,
-    ) -> bool;
+        servers_proof: &StrongProof,
+    ) -> Result<(StrongProof, StrongSessionKey)>;
 }
 
 #[allow(non_snake_case)]
-#[derive(Debug, Serialize)]
+#[derive(Serialize)]
 pub struct Srp6User<const KEY_LENGTH: usize, const SALT_LENGTH: usize> {
     pub A: PublicKey,
     pub B: PublicKey,
+    #[serde(skip)]
     a: PrivateKey,
-    pub U: PublicKey, 
+    pub U: PublicKey,
     pub salt: Salt,
     pub M: Proof,
+    #[serde(skip)]
     S: PrivateKey,
+    #[serde(skip)]
     K: SessionKey,
     verified: bool,
+    multiplier: Option<MultiplierParameter>,
+    identity_hashing: IdentityHashing,
+    identity_separator: Vec<u8>,
+    session_key_serialization: SessionKeySerialization,
+    proof_layout: ProofLayout,
+    padding: PaddingPolicy,
 }
 
 impl<const KEY_LENGTH: usize, const SALT_LENGTH: usize> Srp6User<KEY_LENGTH, SALT_LENGTH> {
@@ -49,66 +73,163 @@ impl<const KEY_LENGTH: usize, const SALT_LENGTH: usize> Srp6User<KEY_LENGTH, SAL
     pub const SALT_LEN: usize = SALT_LENGTH;
 
     pub fn new() -> Self {
-        Self{
+        Self {
             A: PublicKey::default(),
             B: PublicKey::default(),
             a: PrivateKey::default(),
-            U: PublicKey::default(), 
+            U: PublicKey::default(),
             salt: Salt::default(),
             M: Proof::default(),
             S: PrivateKey::default(),
             K: SessionKey::default(),
-            verified: false
+            verified: false,
+            multiplier: None,
+            identity_hashing: IdentityHashing::default(),
+            identity_separator: DEFAULT_IDENTITY_SEPARATOR.to_vec(),
+            session_key_serialization: SessionKeySerialization::default(),
+            proof_layout: ProofLayout::default(),
+            padding: PaddingPolicy::default(),
         }
     }
-}
 
-impl<const KEY_LENGTH: usize, const SALT_LENGTH: usize> UserTrait<KEY_LENGTH, SALT_LENGTH>
-    for Srp6User<KEY_LENGTH, SALT_LENGTH>
-{
+    /// the conventions of SRP-6 before SRP-6a, the counterpart of [`Srp6::legacy_srp6`](crate::Srp6::legacy_srp6)
+    pub fn legacy_srp6() -> Self {
+        Self::new()
+            .with_multiplier(MultiplierParameter::from(3))
+            .with_session_key_serialization(SessionKeySerialization::Minimal)
+    }
+
+    /// uses a custom [`MultiplierParameter`] `k` instead of `k = H(N | PAD(g))`,
+    /// e.g. `k = 3` for legacy SRP-6. The server must use the same `k`.
+    pub fn with_multiplier(mut self, k: MultiplierParameter) -> Self {
+        self.multiplier = Some(k);
+        self
+    }
+
+    /// how the username goes into the private key `x`, the server must use the same
+    pub fn with_identity_hashing(mut self, identity_hashing: IdentityHashing) -> Self {
+        self.identity_hashing = identity_hashing;
+        self
+    }
+
+    /// the bytes between `I` and `p` in `H(I | ":" | p)` of [`IdentityHashing::Rfc2945`],
+    /// `b":"` by default, e.g. none for some implementations, the server must use the same
+    pub fn with_identity_separator(mut self, separator: &[u8]) -> Self {
+        self.identity_separator = separator.to_vec();
+        self
+    }
+
+    /// how `S` is serialized before it is hashed into `K`, e.g. [`SessionKeySerialization::Minimal`]
+    /// to interoperate with reference implementations, the server must use the same
+    pub fn with_session_key_serialization(
+        mut self,
+        serialization: SessionKeySerialization,
+    ) -> Self {
+        self.session_key_serialization = serialization;
+        self
+    }
+
+    /// which parts go into the proof `M1`, e.g. for WoW style servers, the server must use the same
+    pub fn with_proof_layout(mut self, layout: ProofLayout) -> Self {
+        self.proof_layout = layout;
+        self
+    }
+
+    /// which numbers are padded where they are hashed, e.g. to match a peer that hashes
+    /// `A` without leading zeros, all are padded by default, the server must use the same
+    pub fn with_padding(mut self, padding: PaddingPolicy) -> Self {
+        self.padding = padding;
+        self
+    }
+
+    /// the [`MultiplierParameter`] `k` used for the given group
+    pub fn multiplier(&self, constants: &OpenConstants) -> MultiplierParameter {
+        multiplier_or_default::<KEY_LENGTH>(self.multiplier.as_ref(), constants, self.padding)
+    }
+
+    /// `K` as calculated by [`UserTrait::update_handshake`], before the host proved to know `v`
+    pub(crate) fn unverified_session_key(&self) -> &StrongSessionKey {
+        &self.K
+    }
+
+    /// the separator set by [`Srp6User::with_identity_separator`]
+    pub(crate) fn identity_separator(&self) -> &[u8] {
+        &self.identity_separator
+    }
+
+    /// same as [`UserTrait::start_handshake`], but with a given private key `a`,
+    /// e.g. for test vectors like [RFC5054] Appendix B. `a` must be random and never be reused
+    ///
+    /// fails with [`Srp6Error::InvalidPrivateKey`] unless `0 < a < N`
+    ///
+    /// [RFC5054]: https://datatracker.ietf.org/doc/html/rfc5054#appendix-B
+    pub fn start_handshake_with_private_key(
+        &mut self,
+        username: UsernameRef,
+        constants: &OpenConstants,
+        a: ClientPrivateKey,
+    ) -> Result<UserHandshake> {
+        let a = a.into_inner();
+        if a.is_zero() || a >= constants.module {
+            return Err(Srp6Error::InvalidPrivateKey);
+        }
+
+        Ok(self.handshake_with_private_key(username, constants, a))
+    }
 
     #[allow(non_snake_case)]
-    fn start_handshake(
+    fn handshake_with_private_key(
         &mut self,
         username: UsernameRef,
-        constants: &OpenConstants
+        constants: &OpenConstants,
+        a: PrivateKey,
     ) -> UserHandshake {
-
-        let a = generate_private_key::<KEY_LENGTH>();
-        debug!("a = {:?}", &a);
-        
+        telemetry::handshake_started(Side::Client);
         let A = calculate_pubkey_A(&constants.module, &constants.generator, &a);
         self.a = a;
         self.A = A.clone();
 
-        return UserHandshake{
+        UserHandshake {
             username: username.to_owned(),
-            user_publickey: A
+            user_publickey: A,
         }
     }
 
+    /// calculates the proof `M` based on the servers `B` and the salt `s`
     #[allow(non_snake_case)]
-    fn update_handshake(
+    fn calculate_proof(
         &mut self,
-        server_handshake: &ServerHandshake,
         constants: &OpenConstants,
         I: UsernameRef,
         p: &ClearTextPassword,
-    ) -> Result<Proof>{
-        self.B = server_handshake.server_publickey.clone();
-        self.salt = server_handshake.salt.clone();
-        
-        self.U = calculate_u::<KEY_LENGTH>(&self.A, &self.B);
-        let x = calculate_private_key_x(I, p, &self.salt);
+    ) -> Result<Proof> {
+        ensure_group_length::<KEY_LENGTH>(constants).context("while checking the group")?;
+        ensure_non_empty_salt::<SALT_LENGTH>(&self.salt)?;
+        let U = calculate_u::<KEY_LENGTH>(&self.A, &self.B, self.padding);
+        let x = calculate_private_key_x(
+            self.identity_hashing,
+            &self.identity_separator,
+            I,
+            p,
+            &self.salt,
+        );
         self.S = calculate_session_key_S_for_client::<KEY_LENGTH>(
             &constants.module,
+            &self.multiplier(constants),
             &constants.generator,
             &self.B,
             &self.A,
             &self.a,
             &x,
-        )?;
-        self.K = calculate_session_key_hash_interleave_K::<KEY_LENGTH>(&self.S);
+            self.padding,
+        )
+        .context("while computing the user session key S")?;
+        // only set once `S` is known, `u` marks the handshake as ready to verify `M2`
+        self.U = U;
+        self.K = calculate_session_key_hash_interleave_K::<KEY_LENGTH>(
+            &self.S,
+            self.session_key_serialization,
+        );
         self.M = calculate_proof_M::<KEY_LENGTH, SALT_LENGTH>(
             &constants.module,
             &constants.generator,
@@ -117,25 +238,1080 @@ impl<const KEY_LENGTH: usize, const SALT_LENGTH: usize> UserTrait<KEY_LENGTH, SA
             &self.A,
             &self.B,
             &self.K,
+            self.proof_layout,
+            self.padding,
         );
         Ok(self.M.clone())
     }
+}
+
+/// the secrets `a`, `S` and `K` are redacted
+impl<const KEY_LENGTH: usize, const SALT_LENGTH: usize> Debug
+    for Srp6User<KEY_LENGTH, SALT_LENGTH>
+{
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("Srp6User")
+            .field("A", &self.A)
+            .field("B", &self.B)
+            .field("a", &Redacted("PrivateKey"))
+            .field("U", &self.U)
+            .field("salt", &self.salt)
+            .field("M", &self.M)
+            .field("S", &Redacted("SessionKey"))
+            .field("K", &Redacted("StrongSessionKey"))
+            .field("verified", &self.verified)
+            .field("multiplier", &self.multiplier)
+            .field("identity_hashing", &self.identity_hashing)
+            .field("identity_separator", &self.identity_separator)
+            .field("session_key_serialization", &self.session_key_serialization)
+            .field("proof_layout", &self.proof_layout)
+            .field("padding", &self.padding)
+            .finish()
+    }
+}
 
-    fn verify_proof(
+impl<const KEY_LENGTH: usize, const SALT_LENGTH: usize> Default
+    for Srp6User<KEY_LENGTH, SALT_LENGTH>
+{
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<const KEY_LENGTH: usize, const SALT_LENGTH: usize> UserTrait<KEY_LENGTH, SALT_LENGTH>
+    for Srp6User<KEY_LENGTH, SALT_LENGTH>
+{
+    #[allow(non_snake_case)]
+    fn start_handshake(
+        &mut self,
+        username: UsernameRef,
+        constants: &OpenConstants,
+    ) -> UserHandshake {
+        let a = generate_private_key::<KEY_LENGTH>();
+
+        self.handshake_with_private_key(username, constants, a)
+    }
+
+    #[allow(non_snake_case)]
+    fn update_handshake(
         &mut self,
-        servers_proof: &Proof,
-    ) -> bool {
+        server_handshake: &ServerHandshake,
+        constants: &OpenConstants,
+        I: UsernameRef,
+        p: &ClearTextPassword,
+    ) -> Result<Proof> {
+        self.B = server_handshake.server_publickey.clone();
+        self.salt = server_handshake.salt.clone();
 
-        let my_strong_proof = calculate_strong_proof_M2::<KEY_LENGTH>(&self.A, &self.M, &self.K);
+        self.calculate_proof(constants, I, p)
+    }
 
-        if servers_proof != &my_strong_proof {
-            false
-        } else {
+    #[allow(non_snake_case)]
+    fn update_handshake_without_salt(
+        &mut self,
+        server_handshake: &ServerHandshakeNoSalt,
+        salt: &Salt,
+        constants: &OpenConstants,
+        I: UsernameRef,
+        p: &ClearTextPassword,
+    ) -> Result<Proof> {
+        self.B = server_handshake.server_publickey.clone();
+        self.salt = salt.clone();
+
+        self.calculate_proof(constants, I, p)
+    }
+
+    fn verify_proof(
+        &mut self,
+        servers_proof: &StrongProof,
+    ) -> Result<(StrongProof, StrongSessionKey)> {
+        let verification = Verification::start(Side::Client);
+        // refuses to verify before `update_handshake`, `M` and `K` are still zero then
+        if let Err(e) = ensure_nonzero_u(&self.U) {
+            return verification.finish(Err(e));
+        }
+        let my_strong_proof =
+            calculate_strong_proof_M2::<KEY_LENGTH>(&self.A, &self.M, &self.K, self.padding);
+
+        let result = if strong_proofs_match(&my_strong_proof, servers_proof) {
             self.verified = true;
-            true
+            Ok((my_strong_proof, self.K.clone()))
+        } else {
+            Err(Srp6Error::InvalidStrongProof(servers_proof.clone()))
+        };
+
+        verification.finish(result)
+    }
+}
+
+/// feeds the servers [`PublicKey`] `B` in chunks as they arrive, e.g. on a slow link
+///
+/// the chunks are collected in a fixed `KEY_LENGTH` buffer on the stack instead of a growing
+/// one, that is all it saves: `u = H(PAD(A) | PAD(B))` and `S` need `B` as a whole number,
+/// so nothing is hashed before [`ProofBuilder::finish`].
+/// The chunks are expected in little endian byte order, as [`PublicKey`] is serialized.
+#[allow(non_snake_case)]
+pub struct ProofBuilder<'a, const KEY_LENGTH: usize, const SALT_LENGTH: usize> {
+    user: &'a mut Srp6User<KEY_LENGTH, SALT_LENGTH>,
+    constants: &'a OpenConstants,
+    I: UsernameRef<'a>,
+    p: &'a ClearTextPassword,
+    salt: Salt,
+    B: [u8; KEY_LENGTH],
+    received: usize,
+}
+
+impl<'a, const KEY_LENGTH: usize, const SALT_LENGTH: usize>
+    ProofBuilder<'a, KEY_LENGTH, SALT_LENGTH>
+{
+    /// `user` must have started the handshake, it holds `a` and `A`
+    #[allow(non_snake_case)]
+    pub fn new(
+        user: &'a mut Srp6User<KEY_LENGTH, SALT_LENGTH>,
+        constants: &'a OpenConstants,
+        I: UsernameRef<'a>,
+        p: &'a ClearTextPassword,
+        salt: Salt,
+    ) -> Self {
+        Self {
+            user,
+            constants,
+            I,
+            p,
+            salt,
+            B: [0; KEY_LENGTH],
+            received: 0,
         }
     }
+
+    /// appends the next chunk of `B`, fails if `B` grows beyond `KEY_LENGTH` bytes
+    pub fn feed_b_chunk(mut self, chunk: &[u8]) -> Result<Self> {
+        let end = self.received + chunk.len();
+        if end > KEY_LENGTH {
+            return Err(Srp6Error::KeyLengthMismatch {
+                given: end,
+                expected: KEY_LENGTH,
+            });
+        }
+        self.B[self.received..end].copy_from_slice(chunk);
+        self.received = end;
+
+        Ok(self)
+    }
+
+    /// calculates the [`Proof`] `M1` as [`UserTrait::update_handshake`] does,
+    /// and returns it with `A` as [`HandshakeProof`], ready to send to the server
+    pub fn finish(self) -> Result<HandshakeProof> {
+        let server_handshake = ServerHandshake {
+            salt: self.salt,
+            server_publickey: PublicKey::from_bytes_le(&self.B[..self.received]),
+        };
+        let proof =
+            self.user
+                .update_handshake(&server_handshake, self.constants, self.I, self.p)?;
+
+        HandshakeProof::new::<KEY_LENGTH>(self.user.A.clone(), proof)
+    }
 }
 
+#[cfg(feature = "group-2048")]
+pub type Srp6user2048 = Srp6User<256, 256>;
+#[cfg(feature = "group-4096")]
+pub type Srp6user4096 = Srp6User<512, 512>;
+
+#[cfg(test)]
+#[cfg(feature = "group-1024")]
+pub(crate) mod tests {
+    use super::*;
+    use crate::hash::{HashFunc, Update};
+    use crate::{rfc5054_1024, HostAPI, Srp6};
+
+    type Srp6_1024 = Srp6<128, 128>;
+    type Srp6user1024 = Srp6User<128, 128>;
+
+    /// signs up "Bob" with "secret-password" and runs step 1 and 2 of the exchange
+    pub(crate) fn start_exchange(
+        host: &mut Srp6_1024,
+        user: &mut Srp6user1024,
+        constants: &OpenConstants,
+    ) -> (UserDetails, UserHandshake, ServerHandshake) {
+        let details = host.generate_new_user_secrets("Bob", "secret-password", constants);
+        let user_handshake = user.start_handshake("Bob", constants);
+        let server_handshake = host
+            .continue_handshake(&details, &user_handshake, constants)
+            .unwrap();
+
+        (details, user_handshake, server_handshake)
+    }
+
+    /// runs the exchange up to the clients proof `M1`
+    pub(crate) fn handshake(host: &mut Srp6_1024, user: &mut Srp6user1024) -> Proof {
+        let constants = rfc5054_1024();
+        let (_, _, server_handshake) = start_exchange(host, user, &constants);
+        user.update_handshake(&server_handshake, &constants, "Bob", "secret-password")
+            .unwrap()
+    }
+
+    #[test]
+    fn should_authenticate_with_the_changed_password_only() {
+        let constants = rfc5054_1024();
+        let old_details =
+            Srp6_1024::new().generate_new_user_secrets("Bob", "secret-password", &constants);
+        let details = Srp6_1024::new().change_password(&old_details, "new-password", &constants);
+        assert_eq!(details.salt, old_details.salt);
+        assert_ne!(details.verifier, old_details.verifier);
+
+        for (password, is_valid) in [("new-password", true), ("secret-password", false)].iter() {
+            let (mut host, mut user) = (Srp6_1024::new(), Srp6user1024::new());
+            let user_handshake = user.start_handshake("Bob", &constants);
+            let server_handshake = host
+                .continue_handshake(&details, &user_handshake, &constants)
+                .unwrap();
+            let proof = user
+                .update_handshake(&server_handshake, &constants, "Bob", password)
+                .unwrap();
+            assert_eq!(host.verify_proof(&proof).is_ok(), *is_valid);
+        }
+    }
+
+    #[test]
+    fn should_authenticate_with_empty_short_and_whitespace_credentials() {
+        let constants = rfc5054_1024();
+        for (username, password) in [
+            ("", ""),
+            ("", "secret"),
+            ("Bob", ""),
+            ("B", "p"),
+            (" ", "  "),
+        ] {
+            let (mut host, mut user) = (Srp6_1024::new(), Srp6user1024::new());
+            let details = host.generate_new_user_secrets(username, password, &constants);
+            let user_handshake = user.start_handshake(username, &constants);
+            let server_handshake = host
+                .continue_handshake(&details, &user_handshake, &constants)
+                .unwrap();
+            let proof = user
+                .update_handshake(&server_handshake, &constants, username, password)
+                .unwrap();
+
+            let strong_proof = host.verify_proof(&proof).unwrap();
+            assert!(user.verify_proof(&strong_proof).is_ok());
+        }
+    }
+
+    #[test]
+    fn should_not_trim_whitespace_only_passwords() {
+        let constants = rfc5054_1024();
+        let (mut host, mut user) = (Srp6_1024::new(), Srp6user1024::new());
+        let details = host.generate_new_user_secrets("Bob", "  ", &constants);
+        let user_handshake = user.start_handshake("Bob", &constants);
+        let server_handshake = host
+            .continue_handshake(&details, &user_handshake, &constants)
+            .unwrap();
+        let proof = user
+            .update_handshake(&server_handshake, &constants, "Bob", "")
+            .unwrap();
+
+        assert!(host.verify_proof(&proof).is_err());
+    }
+
+    #[test]
+    fn should_refuse_an_empty_salt() {
+        let constants = rfc5054_1024();
+        let empty = Salt::from_hex_str_be("").unwrap();
+        let expected = || Srp6Error::KeyLengthMismatch {
+            given: 0,
+            expected: 128,
+        };
+
+        let mut user = Srp6user1024::new();
+        user.start_handshake("Bob", &constants);
+        let server_handshake = ServerHandshake {
+            salt: empty.clone(),
+            server_publickey: PublicKey::from(5),
+        };
+        assert_eq!(
+            user.update_handshake(&server_handshake, &constants, "Bob", "secret-password"),
+            Err(expected())
+        );
+        assert_eq!(
+            ServerHandshake::new::<128, 128>(empty.clone(), PublicKey::from(5)),
+            Err(expected())
+        );
+
+        let mut host = Srp6_1024::new();
+        let mut details = host.generate_new_user_secrets("Bob", "secret-password", &constants);
+        details.salt = empty;
+        let user_handshake = user.start_handshake("Bob", &constants);
+        assert_eq!(
+            host.continue_handshake(&details, &user_handshake, &constants),
+            Err(expected())
+        );
+    }
 
-pub type Srp6user4096 = Srp6User<512, 512>;
\ No newline at end of file
+    #[test]
+    fn should_keep_concurrent_handshakes_of_the_same_user_apart() {
+        let constants = rfc5054_1024();
+        let details =
+            Srp6_1024::new().generate_new_user_secrets("Bob", "secret-password", &constants);
+        let mut hosts = [Srp6_1024::new(), Srp6_1024::new()];
+        let mut users = [Srp6user1024::new(), Srp6user1024::new()];
+
+        let mut proofs = Vec::new();
+        for (host, user) in hosts.iter_mut().zip(users.iter_mut()) {
+            let user_handshake = user.start_handshake("Bob", &constants);
+            let server_handshake = host
+                .continue_handshake(&details, &user_handshake, &constants)
+                .unwrap();
+            proofs.push(
+                user.update_handshake(&server_handshake, &constants, "Bob", "secret-password")
+                    .unwrap(),
+            );
+        }
+        assert_ne!(hosts[0].B, hosts[1].B);
+
+        // each proof is only valid for its own handshake
+        assert!(hosts[0].verify_proof_only(&proofs[1]).is_err());
+        assert!(hosts[1].verify_proof_only(&proofs[0]).is_err());
+        for (host, proof) in hosts.iter_mut().zip(proofs.iter()) {
+            assert!(host.verify_proof(proof).is_ok());
+        }
+    }
+
+    #[test]
+    fn should_only_verify_the_proof() {
+        let (mut host, mut user) = (Srp6_1024::new(), Srp6user1024::new());
+        let proof = handshake(&mut host, &mut user);
+
+        assert!(host.verify_proof_only(&proof).is_ok());
+        assert!(host.session_key().is_none());
+        assert!(matches!(
+            host.verify_proof_only(&Proof::from(7)),
+            Err(Srp6Error::InvalidProof(_))
+        ));
+    }
+
+    #[test]
+    fn should_verify_the_proof_with_a_reconstructed_host() {
+        let constants = rfc5054_1024();
+        let (mut host, mut user) = (Srp6_1024::new(), Srp6user1024::new());
+        let (details, user_handshake, server_handshake) =
+            start_exchange(&mut host, &mut user, &constants);
+        let b = host.private_key();
+        drop(host);
+
+        let proof = user
+            .update_handshake(&server_handshake, &constants, "Bob", "secret-password")
+            .unwrap();
+        let mut host = Srp6_1024::new()
+            .reconstruct(&details, &user_handshake, &constants, b)
+            .unwrap();
+        assert_eq!(host.B, server_handshake.server_publickey);
+        let strong_proof = host.verify_proof(&proof).unwrap();
+        assert!(user.verify_proof(&strong_proof).is_ok());
+    }
+
+    #[test]
+    fn should_build_the_proof_from_chunks_of_b() {
+        let constants = rfc5054_1024();
+        let (mut host, mut user) = (Srp6_1024::new(), Srp6user1024::new());
+        let (_, user_handshake, server_handshake) =
+            start_exchange(&mut host, &mut user, &constants);
+
+        let b_bytes = server_handshake.server_publickey.to_vec();
+        let mut builder = ProofBuilder::new(
+            &mut user,
+            &constants,
+            "Bob",
+            "secret-password",
+            server_handshake.salt,
+        );
+        for chunk in b_bytes.chunks(7) {
+            builder = builder.feed_b_chunk(chunk).unwrap();
+        }
+        let handshake_proof = builder.finish().unwrap();
+
+        assert_eq!(
+            handshake_proof.user_publickey,
+            user_handshake.user_publickey
+        );
+        assert!(host.verify_proof(&handshake_proof.proof).is_ok());
+    }
+
+    #[test]
+    fn should_not_verify_the_strong_proof_before_the_handshake() {
+        let constants = rfc5054_1024();
+        let mut user = Srp6user1024::new();
+        let user_handshake = user.start_handshake("Bob", &constants);
+        // the `M2` of a zero `M` and `K`, anyone who knows `A` can calculate it
+        let forged = calculate_strong_proof_M2::<128>(
+            &user_handshake.user_publickey,
+            &Proof::default(),
+            &StrongSessionKey::default(),
+            PaddingPolicy::default(),
+        );
+
+        assert_eq!(
+            user.verify_proof(&forged).err(),
+            Some(Srp6Error::ZeroScalar)
+        );
+        assert_eq!(
+            Srp6user1024::new().verify_proof(&forged).err(),
+            Some(Srp6Error::ZeroScalar)
+        );
+    }
+
+    #[test]
+    fn should_refuse_a_b_longer_than_the_key_length() {
+        let constants = rfc5054_1024();
+        let mut user = Srp6user1024::new();
+        let builder = ProofBuilder::new(&mut user, &constants, "Bob", "pw", Salt::from(1));
+
+        assert_eq!(
+            builder
+                .feed_b_chunk(&[1; 100])
+                .unwrap()
+                .feed_b_chunk(&[1; 29])
+                .err(),
+            Some(Srp6Error::KeyLengthMismatch {
+                given: 129,
+                expected: 128
+            })
+        );
+    }
+
+    #[test]
+    fn should_start_the_handshake_with_a_given_private_key() {
+        let constants = rfc5054_1024();
+        let a = ClientPrivateKey::from(PrivateKey::from([0x42; 32]));
+        let first = Srp6user1024::new()
+            .start_handshake_with_private_key("Bob", &constants, a.clone())
+            .unwrap();
+        let second = Srp6user1024::new()
+            .start_handshake_with_private_key("Bob", &constants, a.clone())
+            .unwrap();
+
+        assert_eq!(first, second);
+        assert_eq!(a.into_inner(), PrivateKey::from([0x42; 32]));
+
+        for a in [PrivateKey::from(0), constants.module.clone()] {
+            assert_eq!(
+                Srp6user1024::new().start_handshake_with_private_key(
+                    "Bob",
+                    &constants,
+                    ClientPrivateKey::from(a)
+                ),
+                Err(Srp6Error::InvalidPrivateKey)
+            );
+        }
+    }
+
+    #[test]
+    fn should_reproduce_the_client_public_key_of_rfc5054_appendix_b() {
+        let constants = rfc5054_1024();
+        let a = PrivateKey::from_hex_str_be(
+            "60975527035CF2AD1989806F0407210BC81EDC04E2762A56AFD529DDDA2D4393",
+        )
+        .unwrap();
+        let user_handshake = Srp6User::<128, 16>::new()
+            .start_handshake_with_private_key("alice", &constants, ClientPrivateKey::from(a))
+            .unwrap();
+
+        // A = g^a % N does not depend on the byte order of the hash
+        assert_eq!(
+            user_handshake.user_publickey.to_string(),
+            "61D5E490F6F1B79547B0704C436F523DD0E560F0C64115BB72557EC44352E8903211C04692272D8B2D1A5358A2CF1B6E0BFCF99F921530EC8E39356179EAE45E42BA92AEACED825171E1E8B9AF6D9C03E1327F44BE087EF06530E69F66615261EEF54073CA11CF5858F0EDFDFE15EFEAB349EF5D76988A3672FAC47B0769447B"
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "testing")]
+    fn should_match_the_conformance_report_with_the_defaults() {
+        use crate::compute_user_secrets;
+        use crate::testing::{conformance_report, ConformanceInputs};
+
+        let inputs = ConformanceInputs::rfc5054_appendix_b();
+        let report = conformance_report::<128, 16>(&inputs).unwrap();
+        let constants = &inputs.constants;
+        let mut user = Srp6User::<128, 16>::new();
+        let user_handshake = user
+            .start_handshake_with_private_key(
+                &inputs.username,
+                constants,
+                ClientPrivateKey::from(inputs.a.clone()),
+            )
+            .unwrap();
+        let (_, verifier) = compute_user_secrets(
+            &constants.module,
+            &constants.generator,
+            inputs.salt.clone(),
+            &inputs.username,
+            &inputs.password,
+        );
+        let details = UserDetails {
+            username: inputs.username.clone(),
+            salt: inputs.salt.clone(),
+            verifier,
+        };
+        let mut host = Srp6::<128, 16>::new()
+            .reconstruct(
+                &details,
+                &user_handshake,
+                constants,
+                ServerPrivateKey::from(inputs.b.clone()),
+            )
+            .unwrap();
+        let server_handshake = ServerHandshake {
+            salt: inputs.salt.clone(),
+            server_publickey: host.B.clone(),
+        };
+        let proof = user
+            .update_handshake(
+                &server_handshake,
+                constants,
+                &inputs.username,
+                &inputs.password,
+            )
+            .unwrap();
+
+        assert_eq!(user_handshake.user_publickey.to_string(), report.A);
+        assert_eq!(host.B.to_string(), report.B);
+        assert_eq!(proof.to_string(), report.M1);
+        let strong_proof = host.verify_proof(&proof).unwrap();
+        assert_eq!(strong_proof.to_string(), report.M2);
+        assert!(user.verify_proof(&strong_proof).is_ok());
+    }
+
+    #[test]
+    fn should_only_match_the_same_preset() {
+        let (mut host, mut user) = (Srp6_1024::legacy_srp6(), Srp6user1024::legacy_srp6());
+        let proof = handshake(&mut host, &mut user);
+        assert!(host.verify_proof(&proof).is_ok());
+
+        let (mut host, mut user) = (Srp6_1024::legacy_srp6(), Srp6user1024::new());
+        let proof = handshake(&mut host, &mut user);
+        assert!(matches!(
+            host.verify_proof(&proof),
+            Err(Srp6Error::InvalidProof(_))
+        ));
+    }
+
+    #[test]
+    fn should_return_the_verified_session_with_the_clients_public_key() {
+        let (mut host, mut user) = (Srp6_1024::new(), Srp6user1024::new());
+        let proof = handshake(&mut host, &mut user);
+
+        let session = host.verify_proof_with_session(&proof).unwrap();
+        assert_eq!(session.client_public_key, host.A);
+        assert_eq!(session.group, rfc5054_1024().group_info());
+        assert_eq!(session.group.bits, 1024);
+        assert_eq!(session.group.name(), Some("rfc5054-1024"));
+        let (strong_proof, session_key) = user.verify_proof(&session.strong_proof).unwrap();
+        assert_eq!(strong_proof, session.strong_proof);
+        assert_eq!(session_key, session.session_key);
+    }
+
+    #[test]
+    fn should_send_a_and_the_proof_as_one_buffer() {
+        let (mut host, mut user) = (Srp6_1024::new(), Srp6user1024::new());
+        let proof = handshake(&mut host, &mut user);
+
+        let sent = HandshakeProof::new::<128>(user.A.clone(), proof).unwrap();
+        let bytes = sent.to_concat_bytes::<128>();
+        assert_eq!(bytes.len(), HandshakeProof::serialized_len::<128>());
+        assert_eq!(bytes.len(), 128 + 20);
+        let received = HandshakeProof::from_concat_bytes::<128>(&bytes).unwrap();
+        assert_eq!(received, sent);
+        assert_eq!(received.user_publickey, host.A);
+        assert!(host.verify_proof(&received.proof).is_ok());
+
+        assert_eq!(
+            HandshakeProof::from_concat_bytes::<128>(&bytes[1..]).unwrap_err(),
+            Srp6Error::KeyLengthMismatch {
+                given: 147,
+                expected: 148
+            }
+        );
+    }
+
+    #[test]
+    fn should_send_the_server_handshake_as_one_buffer() {
+        let constants = rfc5054_1024();
+        let (_, _, sent) =
+            start_exchange(&mut Srp6_1024::new(), &mut Srp6user1024::new(), &constants);
+
+        let bytes = sent.to_concat_bytes::<128, 128>();
+        assert_eq!(bytes.len(), ServerHandshake::serialized_len::<128, 128>());
+        assert_eq!(
+            ServerHandshake::from_concat_bytes::<128, 128>(&bytes).unwrap(),
+            sent
+        );
+        assert!(ServerHandshake::from_concat_bytes::<128, 128>(&bytes[1..]).is_err());
+    }
+
+    #[test]
+    fn should_calculate_the_proof_with_a_separately_sent_salt() {
+        let constants = rfc5054_1024();
+        let (mut host, mut user) = (Srp6_1024::new(), Srp6user1024::new());
+        let (details, _, server_handshake) = start_exchange(&mut host, &mut user, &constants);
+
+        let proof = user
+            .update_handshake_without_salt(
+                &server_handshake.without_salt(),
+                &details.salt,
+                &constants,
+                "Bob",
+                "secret-password",
+            )
+            .unwrap();
+        assert!(host.verify_proof(&proof).is_ok());
+    }
+
+    #[test]
+    fn should_fail_with_a_wrong_separately_sent_salt() {
+        let constants = rfc5054_1024();
+        let (mut host, mut user) = (Srp6_1024::new(), Srp6user1024::new());
+        let (_, _, server_handshake) = start_exchange(&mut host, &mut user, &constants);
+
+        let proof = user
+            .update_handshake_without_salt(
+                &server_handshake.without_salt(),
+                &Salt::from(42),
+                &constants,
+                "Bob",
+                "secret-password",
+            )
+            .unwrap();
+        assert!(host.verify_proof(&proof).is_err());
+    }
+
+    #[test]
+    fn should_return_the_strong_proof_and_session_key() {
+        let (mut host, mut user) = (Srp6_1024::new(), Srp6user1024::new());
+        let proof = handshake(&mut host, &mut user);
+        let strong_proof = host.verify_proof(&proof).unwrap();
+
+        let (verified_proof, session_key) = user.verify_proof(&strong_proof).unwrap();
+        assert_eq!(verified_proof, strong_proof);
+        assert_eq!(session_key, user.K);
+        assert_eq!(Some(&session_key), host.session_key());
+    }
+
+    #[test]
+    fn should_authenticate_with_a_legacy_multiplier() {
+        let k = MultiplierParameter::from(3);
+        let mut host = Srp6_1024::new().with_multiplier(k.clone());
+        let mut user = Srp6user1024::new().with_multiplier(k);
+        let proof = handshake(&mut host, &mut user);
+
+        assert!(host.verify_proof(&proof).is_ok());
+    }
+
+    #[test]
+    fn should_fail_for_different_multipliers() {
+        let mut host = Srp6_1024::new().with_multiplier(MultiplierParameter::from(3));
+        let mut user = Srp6user1024::new();
+        let proof = handshake(&mut host, &mut user);
+
+        assert!(host.verify_proof(&proof).is_err());
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn should_pad_the_hashed_numbers_as_configured() {
+        let constants = rfc5054_1024();
+        let (A, B, s) = (PublicKey::from(5), PublicKey::from(7), Salt::from(42));
+        let u = |padding| calculate_u::<128>(&A, &B, padding);
+        let M1 = |padding| {
+            calculate_proof_M::<128, 128>(
+                &constants.module,
+                &constants.generator,
+                "Bob",
+                &s,
+                &A,
+                &B,
+                &StrongSessionKey::from(9),
+                ProofLayout::default(),
+                padding,
+            )
+        };
+        let all_padded = PaddingPolicy::default();
+        let unpadded_A = PaddingPolicy {
+            pad_A: false,
+            ..all_padded
+        };
+        let unpadded_B = PaddingPolicy {
+            pad_B: false,
+            ..all_padded
+        };
+        let unpadded_s = PaddingPolicy {
+            pad_s: false,
+            ..all_padded
+        };
+        let unpadded_g = PaddingPolicy {
+            pad_g: false,
+            ..all_padded
+        };
+        let unpadded_N = PaddingPolicy {
+            pad_N: false,
+            ..all_padded
+        };
+
+        assert_eq!(
+            u(unpadded_A),
+            HashFunc::default()
+                .chain([5])
+                .chain(B.to_array_pad_zero::<128>())
+                .into()
+        );
+        assert_ne!(u(unpadded_B), u(all_padded));
+        assert_eq!(u(unpadded_s), u(all_padded));
+        assert_ne!(M1(unpadded_A), M1(all_padded));
+        assert_ne!(M1(unpadded_B), M1(all_padded));
+        assert_ne!(M1(unpadded_s), M1(all_padded));
+        assert_eq!(M1(unpadded_g), M1(all_padded));
+        assert_eq!(
+            Srp6user1024::new()
+                .with_padding(unpadded_g)
+                .multiplier(&constants),
+            HashFunc::default()
+                .chain(constants.module.to_vec())
+                .chain([2])
+                .into()
+        );
+        assert_ne!(
+            Srp6user1024::new()
+                .with_padding(unpadded_g)
+                .multiplier(&constants),
+            Srp6user1024::new().multiplier(&constants)
+        );
+
+        // `N` has exactly `KEY_LENGTH` bytes in the built-in groups, there `PAD(N)` is `N`
+        assert_eq!(M1(unpadded_N), M1(all_padded));
+        assert_eq!(
+            Srp6user1024::new()
+                .with_padding(unpadded_N)
+                .multiplier(&constants),
+            Srp6user1024::new().multiplier(&constants)
+        );
+        // a 1024 bit group with a `KEY_LENGTH` of 129 bytes
+        let M1_129 = |padding| {
+            calculate_proof_M::<129, 128>(
+                &constants.module,
+                &constants.generator,
+                "Bob",
+                &s,
+                &A,
+                &B,
+                &StrongSessionKey::from(9),
+                ProofLayout::default(),
+                padding,
+            )
+        };
+        assert_ne!(M1_129(unpadded_N), M1_129(all_padded));
+        let k_129 = |padding| {
+            Srp6User::<129, 128>::new()
+                .with_padding(padding)
+                .multiplier(&constants)
+        };
+        assert_ne!(k_129(unpadded_N), k_129(all_padded));
+        assert_eq!(
+            k_129(unpadded_N),
+            Srp6user1024::new().multiplier(&constants)
+        );
+    }
+
+    #[test]
+    fn should_only_authenticate_with_the_same_padding() {
+        let unpadded_g = PaddingPolicy {
+            pad_g: false,
+            ..PaddingPolicy::default()
+        };
+        let (mut host, mut user) = (
+            Srp6_1024::new().with_padding(unpadded_g),
+            Srp6user1024::new().with_padding(unpadded_g),
+        );
+        let proof = handshake(&mut host, &mut user);
+        assert!(host.verify_proof(&proof).is_ok());
+
+        let (mut host, mut user) = (
+            Srp6_1024::new().with_padding(unpadded_g),
+            Srp6user1024::new(),
+        );
+        let proof = handshake(&mut host, &mut user);
+        assert!(matches!(
+            host.verify_proof(&proof),
+            Err(Srp6Error::InvalidProof(_))
+        ));
+    }
+
+    #[test]
+    fn should_calculate_the_multiplier_by_default() {
+        let constants = rfc5054_1024();
+        let expected = calculate_k::<128>(
+            &constants.module,
+            &constants.generator,
+            PaddingPolicy::default(),
+        );
+
+        assert_eq!(Srp6user1024::new().multiplier(&constants), expected);
+        assert_eq!(Srp6_1024::new().multiplier(&constants), expected);
+        assert_eq!(
+            Srp6_1024::new()
+                .with_multiplier(MultiplierParameter::from(3))
+                .multiplier(&constants),
+            MultiplierParameter::from(3)
+        );
+    }
+
+    #[test]
+    fn should_authenticate_with_a_multi_byte_generator() {
+        let rfc = rfc5054_1024();
+        // 40 bytes, longer than the 32 bytes `g` is padded to in `k`
+        let constants = OpenConstants {
+            generator: Generator::from_hex_str_be(
+                "0102030405060708090A0B0C0D0E0F101112131415161718191A1B1C1D1E1F202122232425262728",
+            )
+            .unwrap(),
+            ..rfc.clone()
+        };
+        let (mut host, mut user) = (Srp6_1024::new(), Srp6user1024::new());
+        let (_, _, server_handshake) = start_exchange(&mut host, &mut user, &constants);
+        let proof = user
+            .update_handshake(&server_handshake, &constants, "Bob", "secret-password")
+            .unwrap();
+        let strong_proof = host.verify_proof(&proof).unwrap();
+        assert!(user.verify_proof(&strong_proof).is_ok());
+
+        // all bytes of `g` go into `k`, not just the first 32
+        let truncated = OpenConstants {
+            generator: Generator::from_bytes_le(&constants.generator.to_array_pad_zero::<32>()),
+            ..rfc.clone()
+        };
+        assert_ne!(host.multiplier(&constants), host.multiplier(&truncated));
+        // small generators are padded to 32 bytes as before
+        let mut padded_g = [0_u8; 32];
+        padded_g[0] = 2;
+        let expected: MultiplierParameter = HashFunc::default()
+            .chain(rfc.module.to_vec().as_slice())
+            .chain(padded_g)
+            .into();
+        assert_eq!(host.multiplier(&rfc), expected);
+    }
+
+    #[test]
+    fn should_authenticate_with_password_only_identity_hashing() {
+        let mut host = Srp6_1024::new().with_identity_hashing(IdentityHashing::PasswordOnly);
+        let mut user = Srp6user1024::new().with_identity_hashing(IdentityHashing::PasswordOnly);
+        let proof = handshake(&mut host, &mut user);
+
+        assert!(host.verify_proof(&proof).is_ok());
+    }
+
+    #[test]
+    fn should_authenticate_with_a_custom_identity_separator() {
+        let salt = Salt::from(42);
+        let x = |separator: &[u8]| {
+            calculate_private_key_x(
+                IdentityHashing::Rfc2945,
+                separator,
+                "Bob",
+                "secret-password",
+                &salt,
+            )
+        };
+        assert_ne!(x(b""), x(b":"));
+        assert_ne!(x(b"::"), x(b":"));
+        assert_ne!(x(b""), x(b"::"));
+
+        for separator in [&b""[..], &b"::"[..]] {
+            let mut host = Srp6_1024::new().with_identity_separator(separator);
+            let mut user = Srp6user1024::new().with_identity_separator(separator);
+            let proof = handshake(&mut host, &mut user);
+            assert!(host.verify_proof(&proof).is_ok());
+
+            let mut host = Srp6_1024::new().with_identity_separator(separator);
+            let proof = handshake(&mut host, &mut Srp6user1024::new());
+            assert!(host.verify_proof(&proof).is_err());
+        }
+    }
+
+    #[test]
+    fn should_fail_for_different_identity_hashing() {
+        let mut host = Srp6_1024::new().with_identity_hashing(IdentityHashing::PasswordOnly);
+        let mut user = Srp6user1024::new();
+        let proof = handshake(&mut host, &mut user);
+
+        assert!(host.verify_proof(&proof).is_err());
+    }
+
+    #[test]
+    fn should_authenticate_with_minimal_session_key_serialization() {
+        let serialization = SessionKeySerialization::Minimal;
+        let mut host = Srp6_1024::new().with_session_key_serialization(serialization);
+        let mut user = Srp6user1024::new().with_session_key_serialization(serialization);
+        let proof = handshake(&mut host, &mut user);
+
+        let strong_proof = host.verify_proof(&proof).unwrap();
+        let (_, session_key) = user.verify_proof(&strong_proof).unwrap();
+        assert_eq!(Some(&session_key), host.session_key());
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn should_hash_a_short_session_key_differently_per_serialization() {
+        let K = |S: &SessionKey, serialization| {
+            calculate_session_key_hash_interleave_K::<128>(S, serialization)
+        };
+
+        // a leading zero byte is only hashed in fixed width
+        let S = SessionKey::from_bytes_be(&[0xab; 127]);
+        assert_ne!(
+            K(&S, SessionKeySerialization::FixedWidth),
+            K(&S, SessionKeySerialization::Minimal)
+        );
+
+        let S = SessionKey::from_bytes_be(&[0xab; 128]);
+        assert_eq!(
+            K(&S, SessionKeySerialization::FixedWidth),
+            K(&S, SessionKeySerialization::Minimal)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "pem")]
+    fn should_export_and_import_the_strong_proof_as_pem() {
+        let (mut host, mut user) = (Srp6_1024::new(), Srp6user1024::new());
+        let proof = handshake(&mut host, &mut user);
+        let strong_proof = host.verify_proof(&proof).unwrap();
+
+        let pem = strong_proof.to_pem();
+        assert!(pem.starts_with("-----BEGIN SRP STRONG PROOF-----\n"));
+        let imported = StrongProof::from_pem(&pem).unwrap();
+        assert_eq!(imported, strong_proof);
+        assert!(user.verify_proof(&imported).is_ok());
+
+        let too_long = Proof::from_bytes_be(&[0xff; 21]).to_pem("SRP STRONG PROOF");
+        assert!(StrongProof::from_pem(&too_long).is_err());
+        let wrong_label = proof.to_pem("SRP PROOF");
+        assert!(matches!(
+            StrongProof::from_pem(&wrong_label),
+            Err(crate::BigNumberError::UnexpectedPemLabel { .. })
+        ));
+    }
+
+    #[test]
+    fn should_authenticate_with_the_same_proof_layout() {
+        let wow = ProofLayout {
+            uppercase_identity: true,
+            ..ProofLayout::default()
+        };
+        let without_identity = ProofLayout {
+            include_identity: false,
+            ..ProofLayout::default()
+        };
+        let concatenated = ProofLayout {
+            xor_group_hashes: false,
+            ..ProofLayout::default()
+        };
+
+        for layout in [wow, without_identity, concatenated].iter() {
+            let mut host = Srp6_1024::new().with_proof_layout(*layout);
+            let mut user = Srp6user1024::new().with_proof_layout(*layout);
+            let proof = handshake(&mut host, &mut user);
+            assert!(host.verify_proof(&proof).is_ok(), "{:?}", layout);
+
+            let mut host = Srp6_1024::new();
+            let mut user = Srp6user1024::new().with_proof_layout(*layout);
+            let proof = handshake(&mut host, &mut user);
+            assert!(host.verify_proof(&proof).is_err(), "{:?}", layout);
+        }
+    }
+
+    #[test]
+    fn should_accept_the_configured_proof_layouts_only() {
+        let old = ProofLayout {
+            xor_group_hashes: false,
+            ..ProofLayout::default()
+        };
+        let unexpected = ProofLayout {
+            include_identity: false,
+            ..ProofLayout::default()
+        };
+        let migrating_host = || Srp6_1024::new().with_accepted_proof_layouts(&[old]);
+
+        for layout in [ProofLayout::default(), old].iter().copied() {
+            let mut host = migrating_host();
+            let mut user = Srp6user1024::new().with_proof_layout(layout);
+            let proof = handshake(&mut host, &mut user);
+            assert!(host.verify_proof_only(&proof).is_ok());
+            let (strong_proof, matched) = host.verify_proof_with_layout(&proof).unwrap();
+            assert_eq!(matched, layout);
+            assert!(user.verify_proof(&strong_proof).is_ok(), "{:?}", layout);
+        }
+
+        let mut host = migrating_host();
+        let mut user = Srp6user1024::new().with_proof_layout(unexpected);
+        let proof = handshake(&mut host, &mut user);
+        assert!(matches!(
+            host.verify_proof_with_layout(&proof),
+            Err(Srp6Error::InvalidProof(_))
+        ));
+
+        // opt-in, by default only the configured layout
+        let mut host = Srp6_1024::new();
+        let mut user = Srp6user1024::new().with_proof_layout(old);
+        let proof = handshake(&mut host, &mut user);
+        assert!(matches!(
+            host.verify_proof(&proof),
+            Err(Srp6Error::InvalidProof(_))
+        ));
+    }
+
+    #[test]
+    #[allow(non_snake_case)]
+    fn should_hash_the_identity_into_M1_as_configured() {
+        let constants = rfc5054_1024();
+        let M1 = |I, layout| {
+            calculate_proof_M::<128, 128>(
+                &constants.module,
+                &constants.generator,
+                I,
+                &Salt::from(42),
+                &PublicKey::from(5),
+                &PublicKey::from(7),
+                &StrongSessionKey::from(9),
+                layout,
+                PaddingPolicy::default(),
+            )
+        };
+        let uppercase = ProofLayout {
+            uppercase_identity: true,
+            ..ProofLayout::default()
+        };
+        let without_identity = ProofLayout {
+            include_identity: false,
+            ..ProofLayout::default()
+        };
+
+        assert_ne!(
+            M1("bob", ProofLayout::default()),
+            M1("BOB", ProofLayout::default())
+        );
+        assert_eq!(M1("bob", uppercase), M1("BOB", uppercase));
+        assert_eq!(M1("BOB", uppercase), M1("BOB", ProofLayout::default()));
+        assert_eq!(M1("bob", without_identity), M1("alice", without_identity));
+    }
+
+    #[test]
+    fn should_fail_for_a_wrong_strong_proof() {
+        let (mut host, mut user) = (Srp6_1024::new(), Srp6user1024::new());
+        handshake(&mut host, &mut user);
+
+        let wrong = StrongProof::from_bytes_le(&[42]);
+        assert_eq!(
+            user.verify_proof(&wrong),
+            Err(Srp6Error::InvalidStrongProof(wrong))
+        );
+    }
+}
@@ -0,0 +1,129 @@
+/*!
+A light weight reauthentication for clients that reconnect often,
+without the modpow heavy SRP handshake.
+
+After a successful SRP exchange both sides derive a [`ResumptionTicket`] from the
+[`StrongSessionKey`] `K` and cache it. On reconnect:
+1. the server sends a fresh [`ResumptionChallenge`]
+2. the client answers with `HMAC(ticket, challenge)`, see [`resumption_response`]
+3. the server checks the answer with [`verify_resumption`]
+
+## Security properties
+- the ticket is as sensitive as `K`, whoever holds it can resume the session
+- a resumption proves possession of the ticket, not knowledge of the password
+- there is no forward secrecy, a leaked ticket allows resumption until it expires
+- a challenge must never be used twice, otherwise responses can be replayed
+
+## Expiry
+The ticket carries no lifetime, the server must store it together with an expiry
+(e.g. a few hours) and drop it afterwards, a full SRP handshake is required then.
+*/
+use hmac::{Hmac, Mac};
+use rand::{thread_rng, RngCore};
+
+use crate::hash::{Hash, HashFunc};
+use crate::primitives::StrongSessionKey;
+use crate::{Result, Srp6Error};
+
+type HmacFunc = Hmac<HashFunc>;
+
+/// secret derived from `K` that both sides cache for resumption
+pub type ResumptionTicket = Hash;
+/// random challenge the server sends on reconnect
+pub type ResumptionChallenge = [u8; 32];
+/// the clients answer to a [`ResumptionChallenge`]
+pub type ResumptionResponse = Hash;
+
+const RESUMPTION_LABEL: &[u8] = b"srp6 resumption ticket";
+
+/// derives the [`ResumptionTicket`] from the [`StrongSessionKey`] `K`
+///
+/// formula: `HKDF-Expand(HKDF-Extract(0, PAD(K)), "srp6 resumption ticket")`
+#[allow(non_snake_case)]
+pub fn resumption_ticket(K: &StrongSessionKey) -> ResumptionTicket {
+    let mut ticket = ResumptionTicket::default();
+    K.derive_into(&[RESUMPTION_LABEL], &mut ticket);
+
+    ticket
+}
+
+/// a fresh random [`ResumptionChallenge`], to be used only once
+pub fn resumption_challenge() -> ResumptionChallenge {
+    let mut challenge = [0_u8; 32];
+    thread_rng().fill_bytes(&mut challenge);
+
+    challenge
+}
+
+/// the clients answer to a challenge
+///
+/// formula: `HMAC(ticket, challenge)`
+pub fn resumption_response(
+    ticket: &ResumptionTicket,
+    challenge: &ResumptionChallenge,
+) -> ResumptionResponse {
+    response_mac(ticket, challenge)
+        .finalize()
+        .into_bytes()
+        .into()
+}
+
+/// verifies the clients answer to a challenge in constant time
+pub fn verify_resumption(
+    ticket: &ResumptionTicket,
+    challenge: &ResumptionChallenge,
+    response: &ResumptionResponse,
+) -> Result<()> {
+    response_mac(ticket, challenge)
+        .verify_slice(response)
+        .map_err(|_| Srp6Error::InvalidResumptionResponse)
+}
+
+fn response_mac(ticket: &ResumptionTicket, challenge: &ResumptionChallenge) -> HmacFunc {
+    let mut mac = HmacFunc::new_from_slice(ticket).expect("HMAC accepts keys of any length");
+    mac.update(challenge);
+
+    mac
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn should_resume_with_the_same_ticket() {
+        let ticket = resumption_ticket(&StrongSessionKey::from(42));
+        let challenge = resumption_challenge();
+        let response = resumption_response(&ticket, &challenge);
+
+        assert!(verify_resumption(&ticket, &challenge, &response).is_ok());
+    }
+
+    #[test]
+    fn should_reject_a_response_from_another_session() {
+        let ticket = resumption_ticket(&StrongSessionKey::from(42));
+        let other = resumption_ticket(&StrongSessionKey::from(43));
+        let challenge = resumption_challenge();
+
+        assert_ne!(ticket, other);
+        assert_eq!(
+            verify_resumption(
+                &ticket,
+                &challenge,
+                &resumption_response(&other, &challenge)
+            ),
+            Err(Srp6Error::InvalidResumptionResponse)
+        );
+    }
+
+    #[test]
+    fn should_reject_a_response_to_another_challenge() {
+        let ticket = resumption_ticket(&StrongSessionKey::from(42));
+        let response = resumption_response(&ticket, &[1_u8; 32]);
+
+        assert_eq!(
+            verify_resumption(&ticket, &[2_u8; 32], &response),
+            Err(Srp6Error::InvalidResumptionResponse)
+        );
+    }
+}
@@ -0,0 +1,93 @@
+/*!
+Reauthentication with the [`StrongSessionKey`] `K` of a prior exchange as shared secret.
+
+Unlike a [`ResumptionTicket`](crate::ResumptionTicket) no extra secret is cached,
+both sides keep `K` itself. On reconnect:
+1. the server sends a fresh challenge, see [`resumption_challenge`](crate::resumption_challenge)
+2. the client answers with [`StrongSessionKey::bind_challenge`]
+3. the server checks the answer with [`StrongSessionKey::verify_binding`]
+
+formula: `HKDF-Expand(HKDF-Extract(0, PAD(K)), "srp6 session binding" | challenge)`, 32 bytes
+
+## Freshness
+A challenge must be random and never be used twice, otherwise a recorded answer can be
+replayed. The binding proves possession of `K`, not knowledge of the password, and
+ends together with the session, drop `K` when the session expires.
+*/
+use subtle::ConstantTimeEq;
+
+use crate::primitives::StrongSessionKey;
+use crate::{Result, ResumptionChallenge, Srp6Error};
+
+/// the clients answer to a challenge, bound to `K`
+pub type SessionBinding = [u8; 32];
+
+const SESSION_BINDING_LABEL: &[u8] = b"srp6 session binding";
+
+impl StrongSessionKey {
+    /// the clients answer to the servers `challenge`, proves possession of `K`
+    pub fn bind_challenge(&self, challenge: &ResumptionChallenge) -> SessionBinding {
+        let mut binding = [0_u8; 32];
+        self.derive_into(&[SESSION_BINDING_LABEL, challenge], &mut binding);
+
+        binding
+    }
+
+    /// verifies the clients answer to `challenge` in constant time,
+    /// fails with [`Srp6Error::InvalidSessionBinding`]
+    pub fn verify_binding(
+        &self,
+        challenge: &ResumptionChallenge,
+        binding: &SessionBinding,
+    ) -> Result<()> {
+        if !bool::from(self.bind_challenge(challenge).ct_eq(binding)) {
+            return Err(Srp6Error::InvalidSessionBinding);
+        }
+
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+#[allow(non_snake_case)]
+mod tests {
+    use super::*;
+    use crate::resumption_challenge;
+
+    #[test]
+    fn should_verify_the_binding_of_the_same_session_key() {
+        let K = StrongSessionKey::from(42);
+        let challenge = resumption_challenge();
+        let binding = K.bind_challenge(&challenge);
+
+        assert!(K.verify_binding(&challenge, &binding).is_ok());
+        assert_eq!(
+            StrongSessionKey::from(43).verify_binding(&challenge, &binding),
+            Err(Srp6Error::InvalidSessionBinding)
+        );
+        assert_eq!(
+            K.verify_binding(&resumption_challenge(), &binding),
+            Err(Srp6Error::InvalidSessionBinding)
+        );
+    }
+
+    #[test]
+    #[cfg(feature = "group-1024")]
+    fn should_rebind_a_reconnecting_client_to_the_session() {
+        use crate::api::new_user::tests::handshake;
+        use crate::{HostAPI, Srp6, Srp6User, UserTrait};
+
+        let (mut host, mut user) = (Srp6::<128, 128>::new(), Srp6User::<128, 128>::new());
+        let proof = handshake(&mut host, &mut user);
+        let session = host.verify_proof_with_session(&proof).unwrap();
+        let (_, users_K) = user.verify_proof(&session.strong_proof).unwrap();
+
+        // on reconnect
+        let challenge = resumption_challenge();
+        let binding = users_K.bind_challenge(&challenge);
+        assert!(session
+            .session_key
+            .verify_binding(&challenge, &binding)
+            .is_ok());
+    }
+}
@@ -0,0 +1,84 @@
+/*!
+Runs the whole exchange over any [`Read`] + [`Write`] stream, e.g. a [`std::net::TcpStream`],
+behind the `transport` feature.
+
+[`client_authenticate`] and [`server_authenticate`] drive the steps of the exchange,
+each message is one frame, a `u32` length in big endian followed by the payload:
+1. client: username (UTF-8)
+2. client: `PAD(A)`, both by [`UserHandshake::write_to`]
+3. server: `PAD(s) | PAD(B)`, by [`ServerHandshake::write_to`]
+4. client: `PAD(M1)`
+5. server: `PAD(M2)`, or an empty frame if `M1` is invalid
+
+All numbers are in little endian byte order, as everywhere in this crate.
+*/
+use std::io::{Read, Write};
+
+use crate::api::framing::{read_exact_frame, read_frame, write_frame};
+use crate::hash::HASH_LENGTH;
+use crate::primitives::*;
+use crate::{HostAPI, Result, Srp6, Srp6Error, Srp6User, UserStore, UserTrait};
+
+/// authenticates the client on the other end of `stream`, returns the [`StrongSessionKey`] `K`
+///
+/// the [`UserDetails`] are looked up in `user_store`, unknown users fail with
+/// [`Srp6Error::InvalidUserDetails`], to hide which users exist the store can return
+/// a dummy from [`Srp6::user_details_or_dummy`] instead
+pub fn server_authenticate<S, U, const KEY_LENGTH: usize, const SALT_LENGTH: usize>(
+    stream: &mut S,
+    user_store: &U,
+    srp: &mut Srp6<KEY_LENGTH, SALT_LENGTH>,
+    constants: &OpenConstants,
+) -> Result<StrongSessionKey>
+where
+    S: Read + Write,
+    U: UserStore,
+{
+    let user_handshake = UserHandshake::read_from::<KEY_LENGTH, _>(stream)?;
+    let user_details = user_store
+        .lookup(&user_handshake.username)
+        .ok_or(Srp6Error::InvalidUserDetails("username"))?;
+
+    let server_handshake = srp.continue_handshake(&user_details, &user_handshake, constants)?;
+    server_handshake.write_to::<KEY_LENGTH, SALT_LENGTH, _>(stream)?;
+
+    let proof = Proof::from_bytes_le(&read_exact_frame(stream, HASH_LENGTH)?);
+    match srp.verify_proof_with_session(&proof) {
+        Ok(session) => {
+            write_frame(
+                stream,
+                &session.strong_proof.to_array_pad_zero::<HASH_LENGTH>(),
+            )?;
+            Ok(session.session_key)
+        }
+        Err(e) => {
+            write_frame(stream, &[])?;
+            Err(e)
+        }
+    }
+}
+
+/// authenticates as `username` to the server on the other end of `stream`,
+/// returns the [`StrongSessionKey`] `K` once the server proved to know the verifier
+pub fn client_authenticate<S, const KEY_LENGTH: usize, const SALT_LENGTH: usize>(
+    stream: &mut S,
+    username: UsernameRef,
+    password: &ClearTextPassword,
+    srp: &mut Srp6User<KEY_LENGTH, SALT_LENGTH>,
+    constants: &OpenConstants,
+) -> Result<StrongSessionKey>
+where
+    S: Read + Write,
+{
+    let user_handshake = srp.start_handshake(username, constants);
+    user_handshake.write_to::<KEY_LENGTH, _>(stream)?;
+
+    let server_handshake = ServerHandshake::read_from::<KEY_LENGTH, SALT_LENGTH, _>(stream)?;
+    let proof = srp.update_handshake(&server_handshake, constants, username, password)?;
+    write_frame(stream, &proof.to_array_pad_zero::<HASH_LENGTH>())?;
+
+    let strong_proof = StrongProof::from_bytes_le(&read_frame(stream)?);
+    let (_, session_key) = srp.verify_proof(&strong_proof)?;
+
+    Ok(session_key)
+}
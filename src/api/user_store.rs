@@ -0,0 +1,82 @@
+/*!
+Where the host looks up the [`UserDetails`] of a username, e.g. for
+[`crate::transport::server_authenticate`].
+
+The application implements [`UserStore`] over its own database, for tests and
+single process servers there is [`HashMapUserStore`].
+
+To hide which usernames exist, a store can answer unknown usernames with a dummy
+from [`crate::Srp6::user_details_or_dummy`] instead of `None`.
+*/
+use std::collections::HashMap;
+
+use crate::primitives::{UserDetails, Username, UsernameRef};
+
+/// looks up and stores [`UserDetails`] by username, implemented by the application
+pub trait UserStore {
+    /// the [`UserDetails`] of `username`, `None` if it is unknown
+    fn lookup(&self, username: UsernameRef) -> Option<UserDetails>;
+
+    /// stores `details` under their username, replaces existing ones
+    fn store(&mut self, details: UserDetails);
+}
+
+/// [`UserStore`] that keeps the users in memory
+#[derive(Debug, Clone, Default)]
+pub struct HashMapUserStore {
+    users: HashMap<Username, UserDetails>,
+}
+
+impl HashMapUserStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
+
+impl UserStore for HashMapUserStore {
+    fn lookup(&self, username: UsernameRef) -> Option<UserDetails> {
+        self.users.get(username).cloned()
+    }
+
+    fn store(&mut self, details: UserDetails) {
+        self.users.insert(details.username.clone(), details);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::primitives::{PasswordVerifier, Salt};
+
+    fn details(username: UsernameRef, verifier: u32) -> UserDetails {
+        UserDetails {
+            username: username.to_owned(),
+            salt: Salt::from(42),
+            verifier: PasswordVerifier::from(verifier),
+        }
+    }
+
+    #[test]
+    fn should_look_up_stored_users() {
+        let mut store = HashMapUserStore::new();
+        assert!(store.lookup("Bob").is_none());
+
+        store.store(details("Bob", 1));
+        store.store(details("Alice", 2));
+        assert_eq!(
+            store.lookup("Bob").unwrap().verifier,
+            PasswordVerifier::from(1)
+        );
+        assert!(store.lookup("bob").is_none());
+
+        store.store(details("Bob", 3));
+        assert_eq!(
+            store.lookup("Bob").unwrap().verifier,
+            PasswordVerifier::from(3)
+        );
+        assert_eq!(
+            store.lookup("Alice").unwrap().verifier,
+            PasswordVerifier::from(2)
+        );
+    }
+}
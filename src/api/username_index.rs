@@ -0,0 +1,56 @@
+/*!
+Keeps plain usernames out of the primary index of the user table.
+
+[`Srp6::username_index`] maps a username to a stable, non reversible [`UsernameIndex`],
+the application stores `(salt, verifier)` under it and looks them up the same way.
+
+**NOTE**: the index is for lookups only, it proves nothing and must not be used
+for authentication. All indexes change with the `server_secret`, so it must be
+the same for every call and be kept across restarts.
+*/
+use hkdf::Hkdf;
+
+use crate::hash::HashFunc;
+use crate::primitives::UsernameRef;
+use crate::Srp6;
+
+/// stable key of a username in the user table, see [`Srp6::username_index`]
+pub type UsernameIndex = [u8; 32];
+
+const USERNAME_INDEX_LABEL: &[u8] = b"srp6 username index";
+
+impl<const KEY_LENGTH: usize, const SALT_LENGTH: usize> Srp6<KEY_LENGTH, SALT_LENGTH> {
+    /// the [`UsernameIndex`] of `I` for the user table, not for authentication
+    ///
+    /// formula: `HKDF(server_secret, I, "srp6 username index")`,
+    /// HMAC based to stretch the output to 32 bytes
+    #[allow(non_snake_case)]
+    pub fn username_index(I: UsernameRef, server_secret: &[u8]) -> UsernameIndex {
+        let mut index = [0_u8; 32];
+        Hkdf::<HashFunc>::new(Some(server_secret), I.as_bytes())
+            .expand(USERNAME_INDEX_LABEL, &mut index)
+            .expect("32 is a valid length for HKDF");
+
+        index
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    type Srp6_1024 = Srp6<128, 128>;
+
+    const SERVER_SECRET: &[u8] = b"a random server secret";
+
+    #[test]
+    fn should_derive_a_stable_index_per_username_and_secret() {
+        let index = Srp6_1024::username_index("Bob", SERVER_SECRET);
+        assert_eq!(index, Srp6_1024::username_index("Bob", SERVER_SECRET));
+        assert_eq!(index, Srp6::<256, 32>::username_index("Bob", SERVER_SECRET));
+
+        assert_ne!(index, Srp6_1024::username_index("bob", SERVER_SECRET));
+        assert_ne!(index, Srp6_1024::username_index("Bob", b"another secret"));
+        assert_ne!(&index[..3], b"Bob");
+    }
+}
@@ -0,0 +1,263 @@
+/*!
+Upgrades a stored verifier on login, like a password rehash on login for bcrypt.
+
+The host only knows the verifier `v = g^x % N`, not the password, so it can not
+recompute `v` under another [`IdentityHashing`] or with a fresh salt itself.
+Only the client can, and it does so while it still has the password:
+1. the client calculates a [`NewVerifier`] with [`Srp6User::new_verifier`] after
+   [`crate::UserTrait::update_handshake`] and sends it along with the proof `M1`
+2. the host checks the proof and the [`NewVerifier`] with [`Srp6::verify_proof_with_upgrade`]
+   and stores the new salt and verifier, if one is returned
+
+The [`NewVerifier`] is authenticated with a MAC key derived from `K`, so only the client
+of this very session can replace the verifier, and only after proving the old password.
+
+## Security properties
+- a [`NewVerifier`] that fails the check is ignored, the login itself still succeeds
+- the host can not tell if the new verifier is over the same password,
+  a client can change its password this way
+- the MAC only authenticates the [`NewVerifier`], it is not encrypted. Salt and verifier
+  allow an offline dictionary attack on the password, so they must only be sent over a
+  confidential channel, e.g. TLS, just like at signup
+*/
+use hmac::{Hmac, Mac};
+use rand::thread_rng;
+use serde::Serialize;
+use std::fmt::{Debug, Formatter};
+
+use crate::api::new_host::compute_verifier;
+use crate::big_number::Zero;
+use crate::hash::{Hash, HashFunc};
+use crate::primitives::*;
+use crate::{HostAPI, Result, Srp6, Srp6User};
+
+type HmacFunc = Hmac<HashFunc>;
+
+const NEW_VERIFIER_LABEL: &[u8] = b"srp6 new verifier";
+
+/// fresh [`Salt`] and [`PasswordVerifier`] the client sends to upgrade its stored verifier
+///
+/// **WARNING**: not encrypted, anyone who reads it can run an offline dictionary attack
+/// on the password, send it over a confidential channel only
+#[derive(Clone, PartialEq, Serialize)]
+pub struct NewVerifier {
+    pub salt: Salt,
+    pub verifier: PasswordVerifier,
+    /// how the username went into `x`, the host must use the same from now on
+    pub identity_hashing: IdentityHashing,
+    /// `HMAC(MAC key, identity hashing | PAD(s) | PAD(v))`, the MAC key derived from `K`
+    /// with the label `"srp6 new verifier"`
+    pub mac: Hash,
+}
+
+impl Debug for NewVerifier {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("NewVerifier")
+            .field("salt", &self.salt)
+            .field("verifier", &Redacted("PasswordVerifier"))
+            .field("identity_hashing", &self.identity_hashing)
+            .finish()
+    }
+}
+
+impl<const KEY_LENGTH: usize, const SALT_LENGTH: usize> Srp6User<KEY_LENGTH, SALT_LENGTH> {
+    /// a [`NewVerifier`] with a fresh salt for `identity_hashing`, to be sent with the proof `M1`,
+    /// must be called after [`crate::UserTrait::update_handshake`], as it is bound to `K`
+    ///
+    /// **WARNING**: the result is authenticated but not encrypted, it must only be sent over
+    /// a confidential channel, as it allows an offline dictionary attack on `p`
+    #[allow(non_snake_case)]
+    pub fn new_verifier(
+        &self,
+        I: UsernameRef,
+        p: &ClearTextPassword,
+        identity_hashing: IdentityHashing,
+        constants: &OpenConstants,
+    ) -> NewVerifier {
+        let salt = generate_salt_with_rng::<SALT_LENGTH, _>(&mut thread_rng());
+        let verifier = compute_verifier(
+            identity_hashing,
+            self.identity_separator(),
+            &constants.module,
+            &constants.generator,
+            &salt,
+            I,
+            p,
+        );
+        let mac = new_verifier_mac::<KEY_LENGTH, SALT_LENGTH>(
+            self.unverified_session_key(),
+            identity_hashing,
+            &salt,
+            &verifier,
+        )
+        .finalize()
+        .into_bytes()
+        .into();
+
+        NewVerifier {
+            salt,
+            verifier,
+            identity_hashing,
+            mac,
+        }
+    }
+}
+
+impl<const KEY_LENGTH: usize, const SALT_LENGTH: usize> Srp6<KEY_LENGTH, SALT_LENGTH> {
+    /// same as [`HostAPI::verify_proof_with_session`], and returns the `new_verifier`
+    /// for storage, if it is valid, authenticated by this session and for `new_hardening`
+    ///
+    /// a `new_verifier` that fails any of these checks is ignored, the login still succeeds
+    pub fn verify_proof_with_upgrade(
+        &mut self,
+        users_proof: &Proof,
+        new_verifier: Option<&NewVerifier>,
+        new_hardening: IdentityHashing,
+        constants: &OpenConstants,
+    ) -> Result<(VerifiedSession, Option<NewVerifier>)> {
+        let session = self.verify_proof_with_session(users_proof)?;
+        let upgrade = new_verifier
+            .filter(|new| new.identity_hashing == new_hardening)
+            .filter(|new| Self::validate_salt(&new.salt).is_ok())
+            .filter(|new| !new.verifier.is_zero() && new.verifier < constants.module)
+            .filter(|new| {
+                new_verifier_mac::<KEY_LENGTH, SALT_LENGTH>(
+                    &session.session_key,
+                    new.identity_hashing,
+                    &new.salt,
+                    &new.verifier,
+                )
+                .verify_slice(&new.mac)
+                .is_ok()
+            })
+            .cloned();
+
+        Ok((session, upgrade))
+    }
+}
+
+#[allow(non_snake_case)]
+fn new_verifier_mac<const KEY_LENGTH: usize, const SALT_LENGTH: usize>(
+    K: &StrongSessionKey,
+    identity_hashing: IdentityHashing,
+    s: &Salt,
+    v: &PasswordVerifier,
+) -> HmacFunc {
+    let identity_hashing: u8 = match identity_hashing {
+        IdentityHashing::Rfc2945 => 0,
+        IdentityHashing::PasswordOnly => 1,
+    };
+    let mut mac_key = Hash::default();
+    K.derive_into(&[NEW_VERIFIER_LABEL], &mut mac_key);
+    let mut mac = HmacFunc::new_from_slice(&mac_key).expect("HMAC accepts keys of any length");
+    mac.update(&[identity_hashing]);
+    mac.update(&s.to_array_pad_zero::<SALT_LENGTH>());
+    mac.update(&v.to_array_pad_zero::<KEY_LENGTH>());
+
+    mac
+}
+
+#[cfg(test)]
+#[cfg(feature = "group-1024")]
+mod tests {
+    use super::*;
+    use crate::{rfc5054_1024, Srp6Error, UserTrait};
+
+    type Srp6_1024 = Srp6<128, 128>;
+    type Srp6user1024 = Srp6User<128, 128>;
+
+    /// a legacy user with a [`IdentityHashing::PasswordOnly`] verifier logs in and
+    /// sends a [`NewVerifier`] for [`IdentityHashing::Rfc2945`] along with the proof
+    fn login(password: &str) -> (Srp6_1024, Proof, NewVerifier) {
+        let constants = rfc5054_1024();
+        let legacy = IdentityHashing::PasswordOnly;
+        let mut host = Srp6_1024::new().with_identity_hashing(legacy);
+        let mut user = Srp6user1024::new().with_identity_hashing(legacy);
+        let details = host.generate_new_user_secrets("Bob", "secret-password", &constants);
+        let user_handshake = user.start_handshake("Bob", &constants);
+        let server_handshake = host
+            .continue_handshake(&details, &user_handshake, &constants)
+            .unwrap();
+        let proof = user
+            .update_handshake(&server_handshake, &constants, "Bob", password)
+            .unwrap();
+        let new_verifier = user.new_verifier("Bob", password, IdentityHashing::Rfc2945, &constants);
+
+        (host, proof, new_verifier)
+    }
+
+    #[test]
+    fn should_upgrade_the_verifier_on_login() {
+        let constants = rfc5054_1024();
+        let (mut host, proof, new_verifier) = login("secret-password");
+        let (_, upgrade) = host
+            .verify_proof_with_upgrade(
+                &proof,
+                Some(&new_verifier),
+                IdentityHashing::Rfc2945,
+                &constants,
+            )
+            .unwrap();
+        let upgrade = upgrade.unwrap();
+        assert_eq!(upgrade, new_verifier);
+
+        // the next login runs with the upgraded verifier
+        let details = UserDetails {
+            username: "Bob".to_owned(),
+            salt: upgrade.salt,
+            verifier: upgrade.verifier,
+        };
+        let mut host = Srp6_1024::new();
+        let mut user = Srp6user1024::new();
+        let user_handshake = user.start_handshake("Bob", &constants);
+        let server_handshake = host
+            .continue_handshake(&details, &user_handshake, &constants)
+            .unwrap();
+        let proof = user
+            .update_handshake(&server_handshake, &constants, "Bob", "secret-password")
+            .unwrap();
+        assert!(host.verify_proof(&proof).is_ok());
+    }
+
+    #[test]
+    fn should_ignore_an_unexpected_or_tampered_new_verifier() {
+        let constants = rfc5054_1024();
+        let (mut host, proof, new_verifier) = login("secret-password");
+        let mut upgrade = |new_verifier: Option<&NewVerifier>, new_hardening| {
+            let (_, upgrade) = host
+                .verify_proof_with_upgrade(&proof, new_verifier, new_hardening, &constants)
+                .unwrap();
+            upgrade
+        };
+
+        let mut tampered_verifier = new_verifier.clone();
+        tampered_verifier.verifier = &tampered_verifier.verifier + &PasswordVerifier::from(1);
+        let mut tampered_mac = new_verifier.clone();
+        tampered_mac.mac[0] ^= 0x01;
+        let mut weak_salt = new_verifier.clone();
+        weak_salt.salt = Salt::from(0);
+
+        assert!(upgrade(None, IdentityHashing::Rfc2945).is_none());
+        assert!(upgrade(Some(&new_verifier), IdentityHashing::PasswordOnly).is_none());
+        for new_verifier in [tampered_verifier, tampered_mac, weak_salt].iter() {
+            assert!(upgrade(Some(new_verifier), IdentityHashing::Rfc2945).is_none());
+        }
+        assert!(upgrade(Some(&new_verifier), IdentityHashing::Rfc2945).is_some());
+    }
+
+    #[test]
+    fn should_not_upgrade_for_a_wrong_password() {
+        let constants = rfc5054_1024();
+        let (mut host, proof, new_verifier) = login("wrong-password");
+
+        assert!(matches!(
+            host.verify_proof_with_upgrade(
+                &proof,
+                Some(&new_verifier),
+                IdentityHashing::Rfc2945,
+                &constants,
+            ),
+            Err(Srp6Error::InvalidProof(_))
+        ));
+    }
+}
@@ -1,5 +1,5 @@
 use num_bigint::{BigUint, RandBigInt};
-use rand::thread_rng;
+use rand::{thread_rng, CryptoRng, RngCore};
 use serde::{Serialize, Serializer};
 use sha1::{Digest, Sha1};
 use std::convert::TryFrom;
@@ -19,14 +19,26 @@ impl Serialize for BigNumber {
     }
 }
 
+/// the longest number in bytes parsed from a hex string of unknown origin, e.g. by
+/// [`TryFrom<&str>`] or serde, twice the biggest built-in group of 4096 bits
+pub const MAX_HEX_STR_BYTES: usize = 1024;
+
 /// [`BigNumber`] helps to work with big numbers as in openssl used.
 #[derive(PartialEq, Clone, PartialOrd)]
 pub struct BigNumber(BigUint);
 
-#[derive(Error, Debug)]
+#[derive(Error, Debug, PartialEq)]
 pub enum BigNumberError {
     #[error("Invalid hex string.")]
     InvalidHexStr,
+    #[error("The hex string ({given} byte) is longer than expected ({expected} byte).")]
+    KeyLengthMismatch { given: usize, expected: usize },
+    #[error("Invalid DER encoded INTEGER.")]
+    InvalidDer,
+    #[error("Invalid PEM block.")]
+    InvalidPem,
+    #[error("Unexpected PEM label `{given}`, expected `{expected}`.")]
+    UnexpectedPemLabel { given: String, expected: String },
 }
 
 /// new empty unsigned big number
@@ -39,11 +51,31 @@ impl Default for BigNumber {
 impl BigNumber {
     /// new random initialized big number
     pub fn new_rand(n_bytes: usize) -> Self {
-        let mut rng = thread_rng();
-        let a = rng.gen_biguint((n_bytes * 8) as u64);
-        // let a = if a.is_negative() { a.abs() } else { a };
+        Self::new_rand_with(&mut thread_rng(), n_bytes)
+    }
 
-        Self(a)
+    /// new random initialized big number drawn from `rng`
+    pub fn new_rand_with<R: RngCore + CryptoRng>(rng: &mut R, n_bytes: usize) -> Self {
+        Self(rng.gen_biguint((n_bytes * 8) as u64))
+    }
+
+    /// deterministic bytes that look random, e.g. a `Salt` for snapshot tests,
+    /// never use it for secrets or salts in production
+    ///
+    /// formula: `H(seed | 0) | H(seed | 1) | ...` cut to `n_bytes`, in little endian byte order
+    #[cfg(feature = "testing")]
+    pub fn from_seed(seed: u64, n_bytes: usize) -> Self {
+        let bytes: Vec<u8> = (0_u64..)
+            .flat_map(|counter| {
+                Sha1::new()
+                    .chain_update(seed.to_le_bytes())
+                    .chain_update(counter.to_le_bytes())
+                    .finalize()
+            })
+            .take(n_bytes)
+            .collect();
+
+        Self::from_bytes_le(&bytes)
     }
 
     /// [`raw`] is expected to be big endian
@@ -56,12 +88,13 @@ impl BigNumber {
         Self(BigUint::from_bytes_le(raw))
     }
 
-    /// from a hex string, hex strings are always big endian:
+    /// from a hex string of any length, for trusted input like group constants,
+    /// hex strings are always big endian:
     /// High
     ///    -> Low
     ///  "123acab"
     pub fn from_hex_str_be(str: &str) -> std::result::Result<Self, BigNumberError> {
-        let str = if str.len() % 2 != 0 {
+        let str = if !str.len().is_multiple_of(2) {
             format!("{:0>len$}", str, len = (str.len() / 2 + 1) * 2)
         } else {
             str.to_owned()
@@ -74,12 +107,147 @@ impl BigNumber {
         ))
     }
 
+    /// same as [`Self::from_hex_str_be`], but for untrusted input e.g. the clients `A`,
+    /// a string for more than `max_bytes` bytes is refused before anything is decoded
+    pub fn from_hex_str_be_bounded(
+        str: &str,
+        max_bytes: usize,
+    ) -> std::result::Result<Self, BigNumberError> {
+        if str.len() > 2 * max_bytes {
+            return Err(BigNumberError::KeyLengthMismatch {
+                given: str.len().div_ceil(2),
+                expected: max_bytes,
+            });
+        }
+
+        Self::from_hex_str_be(str)
+    }
+
+    /// from a DER encoded (positive) INTEGER, e.g. taken from an ASN.1 structure
+    /// the leading sign byte `0x00` is allowed, negative numbers are rejected
+    pub fn from_der(raw: &[u8]) -> std::result::Result<Self, BigNumberError> {
+        const INTEGER_TAG: u8 = 0x02;
+
+        let (tag, rest) = raw.split_first().ok_or(BigNumberError::InvalidDer)?;
+        if *tag != INTEGER_TAG {
+            return Err(BigNumberError::InvalidDer);
+        }
+        let (len, rest) = rest.split_first().ok_or(BigNumberError::InvalidDer)?;
+        let (len, content) = if len & 0x80 == 0 {
+            (*len as usize, rest)
+        } else {
+            let len_bytes = (len & 0x7f) as usize;
+            if len_bytes == 0 || len_bytes > std::mem::size_of::<usize>() || rest.len() < len_bytes
+            {
+                return Err(BigNumberError::InvalidDer);
+            }
+            let (len, content) = rest.split_at(len_bytes);
+            let len = len.iter().fold(0_usize, |len, b| (len << 8) | *b as usize);
+            (len, content)
+        };
+        if len == 0 || content.len() != len || content[0] & 0x80 != 0 {
+            return Err(BigNumberError::InvalidDer);
+        }
+
+        Ok(Self::from_bytes_be(content))
+    }
+
+    /// as labeled text block in big endian byte order, e.g. for `label` `"SRP SESSION KEY"`:
+    /// ```text
+    /// -----BEGIN SRP SESSION KEY-----
+    /// <base64, wrapped at 64 characters>
+    /// -----END SRP SESSION KEY-----
+    /// ```
+    #[cfg(feature = "pem")]
+    pub fn to_pem(&self, label: &str) -> String {
+        use base64::Engine;
+
+        let encoded = base64::engine::general_purpose::STANDARD.encode(self.to_bytes_be());
+        let mut pem = format!("-----BEGIN {}-----\n", label);
+        for line in encoded.as_bytes().chunks(64) {
+            pem.push_str(std::str::from_utf8(line).expect("base64 is ASCII"));
+            pem.push('\n');
+        }
+        pem.push_str(&format!("-----END {}-----\n", label));
+
+        pem
+    }
+
+    /// reverse of [`BigNumber::to_pem`], fails with [`BigNumberError::UnexpectedPemLabel`]
+    /// if the label is not `expected_label` and [`BigNumberError::InvalidPem`] for malformed blocks
+    #[cfg(feature = "pem")]
+    pub fn from_pem(pem: &str, expected_label: &str) -> std::result::Result<Self, BigNumberError> {
+        use base64::Engine;
+
+        let mut lines = pem.trim().lines().map(str::trim);
+        let label = lines
+            .next()
+            .and_then(|line| line.strip_prefix("-----BEGIN "))
+            .and_then(|line| line.strip_suffix("-----"))
+            .ok_or(BigNumberError::InvalidPem)?;
+        if label != expected_label {
+            return Err(BigNumberError::UnexpectedPemLabel {
+                given: label.to_owned(),
+                expected: expected_label.to_owned(),
+            });
+        }
+        let end = format!("-----END {}-----", label);
+        let mut encoded = String::new();
+        let mut ended = false;
+        for line in lines {
+            if ended {
+                return Err(BigNumberError::InvalidPem);
+            }
+            if line == end {
+                ended = true;
+            } else {
+                encoded.push_str(line);
+            }
+        }
+        if !ended {
+            return Err(BigNumberError::InvalidPem);
+        }
+        let bytes = base64::engine::general_purpose::STANDARD
+            .decode(encoded)
+            .map_err(|_| BigNumberError::InvalidPem)?;
+
+        Ok(Self::from_bytes_be(&bytes))
+    }
+
     pub fn modpow(&self, exponent: &Self, modulo: &Self) -> Self {
         self.0.modpow(&exponent.0, &modulo.0).into()
     }
 
+    /// overwrites the digits with zeros in place, e.g. for a secret that is no longer needed,
+    /// the number is 0 afterwards
+    ///
+    /// best effort, copies made before (e.g. by `clone`) are not affected
+    pub(crate) fn wipe(&mut self) {
+        let bits = self.0.bits();
+        if bits == 0 {
+            return;
+        }
+        // the top bit last, clearing it truncates the digits
+        for bit in 0..bits - 1 {
+            self.0.set_bit(bit, false);
+        }
+        std::hint::black_box(&self.0);
+        self.0.set_bit(bits - 1, false);
+    }
+
+    /// the number of significant bits
+    pub fn num_bits(&self) -> usize {
+        self.0.bits() as usize
+    }
+
     pub fn num_bytes(&self) -> usize {
-        (self.0.bits() as usize + 7) / 8
+        (self.0.bits() as usize).div_ceil(8)
+    }
+
+    /// `ceil(bits / 8)`, for a prime modulus `N` this is the `KEY_LENGTH`
+    /// all keys and proofs are padded to
+    pub fn byte_len(&self) -> usize {
+        self.num_bytes()
     }
 
     /// returns the byte vec in little endian byte order
@@ -87,19 +255,103 @@ impl BigNumber {
         self.0.to_bytes_le()
     }
 
+    /// returns the byte vec in big endian byte order, as e.g. published in RFCs
+    pub fn to_bytes_be(&self) -> Vec<u8> {
+        self.0.to_bytes_be()
+    }
+
+    /// big endian bytes without leading zeros, to diff with other implementations,
+    /// next to [`Self::bytes_padded`] to spot which of both the other side hashed
+    pub fn bytes_minimal(&self) -> Vec<u8> {
+        self.to_bytes_be()
+    }
+
+    /// big endian bytes left padded with zeros to `len`, as `PAD()` in RFC5054,
+    /// a longer number is returned as is, not truncated
+    pub fn bytes_padded(&self, len: usize) -> Vec<u8> {
+        let minimal = self.to_bytes_be();
+        let mut padded = vec![0_u8; len.saturating_sub(minimal.len())];
+        padded.extend_from_slice(&minimal);
+
+        padded
+    }
+
     pub fn to_array<const N: usize>(&self) -> [u8; N] {
         self.to_array_pad_zero::<N>()
     }
 
     /// returns the byte vec in little endian byte order, padded by 0 for `len` bytes
+    ///
+    /// works on the digits directly, so no allocation happens
     pub fn to_array_pad_zero<const N: usize>(&self) -> [u8; N] {
         let mut r = [0_u8; N];
-        for (i, x) in self.to_vec().iter().take(N).enumerate() {
-            r[i] = *x;
+        let bytes = self.0.iter_u64_digits().flat_map(u64::to_le_bytes);
+        for (i, x) in bytes.take(N).enumerate() {
+            r[i] = x;
         }
 
         r
     }
+
+    /// Miller-Rabin test with `rounds` random bases,
+    /// a composite number passes with a probability of at most `4^-rounds`
+    pub fn is_probably_prime(&self, rounds: usize) -> bool {
+        let n = &self.0;
+        let one = BigUint::from(1_u32);
+        let two = BigUint::from(2_u32);
+        if n < &two {
+            return false;
+        }
+        for p in [2_u32, 3, 5, 7, 11, 13, 17, 19, 23, 29, 31, 37] {
+            if (n % p).is_zero() {
+                return *n == BigUint::from(p);
+            }
+        }
+
+        // n - 1 = 2^s * d with d odd
+        let n_minus_one = n - &one;
+        let s = n_minus_one.trailing_zeros().unwrap_or_default();
+        let d = &n_minus_one >> s;
+        let mut rng = thread_rng();
+        'witness: for _ in 0..rounds {
+            let a = rng.gen_biguint_range(&two, &n_minus_one);
+            let mut x = a.modpow(&d, n);
+            if x == one || x == n_minus_one {
+                continue;
+            }
+            for _ in 1..s {
+                x = &x * &x % n;
+                if x == n_minus_one {
+                    continue 'witness;
+                }
+            }
+            return false;
+        }
+
+        true
+    }
+
+    /// `self` and `(self - 1) / 2` are both (probable) primes, see [`BigNumber::is_probably_prime`]
+    pub fn is_safe_prime(&self, rounds: usize) -> bool {
+        let q = Self(&self.0 >> 1);
+
+        self.is_probably_prime(rounds) && q.is_probably_prime(rounds)
+    }
+}
+
+#[test]
+fn should_detect_primes() {
+    for prime in [2_u32, 37, 41, 7919, 2_147_483_647] {
+        assert!(BigNumber::from(prime).is_probably_prime(16), "{}", prime);
+    }
+    // 561 is a Carmichael number
+    for composite in [0_u32, 1, 561, 7917, 41 * 43, 2_147_483_649] {
+        assert!(
+            !BigNumber::from(composite).is_probably_prime(16),
+            "{}",
+            composite
+        );
+    }
 }
 
 #[test]
@@ -138,6 +390,8 @@ impl From<BigUint> for BigNumber {
     }
 }
 
+/// infallible, the length is checked at compile time, e.g. `Proof::from([0_u8; 20])`,
+/// bytes are in little endian byte order, the reverse of [`BigNumber::to_array_pad_zero`]
 impl<const N: usize> From<[u8; N]> for BigNumber {
     fn from(k: [u8; N]) -> Self {
         Self::from_bytes_le(&k)
@@ -171,16 +425,18 @@ impl From<BigNumber> for String {
 impl TryFrom<&str> for BigNumber {
     type Error = BigNumberError;
 
+    /// refuses strings for more than [`MAX_HEX_STR_BYTES`] bytes before decoding
     fn try_from(value: &str) -> std::result::Result<Self, Self::Error> {
-        Self::from_hex_str_be(value)
+        Self::from_hex_str_be_bounded(value, MAX_HEX_STR_BYTES)
     }
 }
 
 impl TryFrom<String> for BigNumber {
     type Error = BigNumberError;
 
+    /// refuses strings for more than [`MAX_HEX_STR_BYTES`] bytes before decoding
     fn try_from(value: String) -> Result<Self, Self::Error> {
-        Self::from_hex_str_be(value.as_str())
+        Self::try_from(value.as_str())
     }
 }
 
@@ -193,18 +449,158 @@ fn should_try_from_string() {
     assert_eq!(x.to_vec(), &[0xcd, 0x11, 0xab]);
 }
 
+#[test]
+fn should_refuse_too_long_hex_strings_early() {
+    let x = BigNumber::from_hex_str_be_bounded("00ab11cd", 4).unwrap();
+    assert_eq!(x.to_vec(), &[0xcd, 0x11, 0xab]);
+    assert!(matches!(
+        BigNumber::from_hex_str_be_bounded("1ab11cd", 3),
+        Err(BigNumberError::KeyLengthMismatch {
+            given: 4,
+            expected: 3
+        })
+    ));
+
+    let huge = "f".repeat(10 * 1024 * 1024);
+    assert!(matches!(
+        BigNumber::from_hex_str_be_bounded(&huge, 128),
+        Err(BigNumberError::KeyLengthMismatch {
+            given: 5_242_880,
+            expected: 128
+        })
+    ));
+}
+
+#[test]
+fn should_refuse_too_long_hex_strings_on_try_from() {
+    let longest = "f".repeat(2 * MAX_HEX_STR_BYTES);
+    assert_eq!(
+        BigNumber::try_from(longest.as_str()).map(|x| x.num_bytes()),
+        Ok(MAX_HEX_STR_BYTES)
+    );
+
+    let huge = "f".repeat(10 * 1024 * 1024);
+    let too_long = Err(BigNumberError::KeyLengthMismatch {
+        given: 5_242_880,
+        expected: MAX_HEX_STR_BYTES,
+    });
+    assert_eq!(BigNumber::try_from(huge.as_str()), too_long);
+    assert_eq!(BigNumber::try_from(huge), too_long);
+}
+
+#[test]
+fn should_return_minimal_and_padded_bytes() {
+    let x = BigNumber::from_hex_str_be("00ab11cd").unwrap();
+    assert_eq!(x.bytes_minimal(), &[0xab, 0x11, 0xcd]);
+    assert_eq!(x.bytes_padded(5), &[0x00, 0x00, 0xab, 0x11, 0xcd]);
+    assert_eq!(x.bytes_padded(3), x.bytes_minimal());
+    assert_eq!(x.bytes_padded(2), x.bytes_minimal());
+}
+
 #[test]
 fn should_from_bytes() {
     let x = BigNumber::from_bytes_be(&[0xab, 0x11, 0xcd]);
     assert_eq!(x.to_vec(), &[0xcd, 0x11, 0xab]);
 }
 
+#[test]
+#[cfg(feature = "testing")]
+fn should_expand_a_seed() {
+    let salt = BigNumber::from_seed(42, 32);
+    assert_eq!(salt, BigNumber::from_seed(42, 32));
+    assert_ne!(salt, BigNumber::from_seed(43, 32));
+    // the first block is the same for all lengths
+    assert_eq!(
+        BigNumber::from_seed(42, 20).to_array_pad_zero::<20>(),
+        salt.to_array_pad_zero::<20>()
+    );
+}
+
+#[test]
+#[cfg(feature = "pem")]
+fn should_export_and_import_pem() {
+    let key = BigNumber::from_hex_str_be(&"ab".repeat(60)).unwrap();
+    let pem = key.to_pem("SRP SESSION KEY");
+    assert!(pem.starts_with("-----BEGIN SRP SESSION KEY-----\n"));
+    assert!(pem.ends_with("-----END SRP SESSION KEY-----\n"));
+    assert!(pem.lines().all(|line| line.len() <= 64));
+    assert_eq!(BigNumber::from_pem(&pem, "SRP SESSION KEY").unwrap(), key);
+
+    assert!(matches!(
+        BigNumber::from_pem(&pem, "SRP STRONG PROOF"),
+        Err(BigNumberError::UnexpectedPemLabel { .. })
+    ));
+    for malformed in [
+        "",
+        "-----BEGIN SRP SESSION KEY-----\nq6ur\n",
+        "-----BEGIN SRP SESSION KEY-----\n!!!!\n-----END SRP SESSION KEY-----\n",
+        "-----BEGIN SRP SESSION KEY-----\n-----END SRP PROOF-----\n",
+    ]
+    .iter()
+    {
+        assert!(matches!(
+            BigNumber::from_pem(malformed, "SRP SESSION KEY"),
+            Err(BigNumberError::InvalidPem)
+        ));
+    }
+}
+
+#[test]
+fn should_from_array() {
+    use crate::primitives::{Proof, PublicKey};
+
+    let proof = [0xab_u8; 20];
+    assert_eq!(Proof::from(proof).to_array_pad_zero::<20>(), proof);
+
+    let mut key = [0_u8; 128];
+    key[0] = 0x05;
+    assert_eq!(PublicKey::from(key), PublicKey::from(5));
+}
+
 #[test]
 fn should_to_vec() {
     let x = BigNumber::from_hex_str_be("ab11cd").unwrap();
     assert_eq!(x.to_vec(), &[0xcd, 0x11, 0xab]);
 }
 
+#[test]
+fn should_to_bytes_be() {
+    let x = BigNumber::from_hex_str_be("ab11cd").unwrap();
+    assert_eq!(x.to_bytes_be(), &[0xab, 0x11, 0xcd]);
+}
+
+#[test]
+fn should_from_der() {
+    let x = BigNumber::from_der(&[0x02, 0x01, 0x05]).unwrap();
+    assert_eq!(x, BigNumber::from(5));
+
+    // with the leading sign byte
+    let x = BigNumber::from_der(&[0x02, 0x03, 0x00, 0xab, 0xcd]).unwrap();
+    assert_eq!(x.to_vec(), &[0xcd, 0xab]);
+
+    // long form length
+    let mut raw = vec![0x02, 0x81, 0x81, 0x00];
+    raw.extend_from_slice(&[0xff; 128]);
+    assert_eq!(BigNumber::from_der(&raw).unwrap().num_bytes(), 128);
+}
+
+#[test]
+fn should_reject_invalid_der() {
+    for raw in [
+        &[][..],
+        &[0x02],
+        &[0x04, 0x01, 0x05],
+        &[0x02, 0x00],
+        &[0x02, 0x02, 0x05],
+        &[0x02, 0x01, 0x05, 0x00],
+        &[0x02, 0x01, 0x80],
+        &[0x02, 0x80, 0x05],
+        &[0x02, 0x82, 0x01],
+    ] {
+        assert!(BigNumber::from_der(raw).is_err(), "{:?}", raw);
+    }
+}
+
 #[test]
 fn should_random_initialize() {
     let x = BigNumber::new_rand(10);
@@ -282,7 +678,7 @@ impl Add for BigNumber {
         self.0.add(rhs.0).into()
     }
 }
-impl<'a, 'b> Add<&'b BigNumber> for &'a BigNumber {
+impl<'b> Add<&'b BigNumber> for &BigNumber {
     type Output = BigNumber;
 
     fn add(self, rhs: &'b BigNumber) -> Self::Output {
@@ -303,13 +699,25 @@ fn should_subtract() {
     assert_eq!(a - b, BigNumber::from(5));
 }
 
-impl<'a, 'b> Sub<&'b BigNumber> for &'a BigNumber {
+impl<'b> Sub<&'b BigNumber> for &BigNumber {
     type Output = BigNumber;
 
     fn sub(self, rhs: &'b BigNumber) -> Self::Output {
         (&self.0).sub(&rhs.0).into()
     }
 }
+#[test]
+fn should_wipe_the_digits() {
+    let mut x = BigNumber::from_bytes_le(&[0xAB; 128]);
+    x.wipe();
+    assert!(x.is_zero());
+    assert_eq!(x.to_array_pad_zero::<128>(), [0; 128]);
+
+    let mut zero = BigNumber::default();
+    zero.wipe();
+    assert!(zero.is_zero());
+}
+
 #[test]
 fn should_subtract_refs() {
     let (a, b) = (BigNumber::from(6), BigNumber::from(6));
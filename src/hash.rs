@@ -1,32 +1,23 @@
 pub use sha1::digest::Update;
 pub use sha1::Digest;
 
-use crate::big_number::BigNumber;
-
 pub const HASH_LENGTH: usize = 20;
 pub type Hash = [u8; HASH_LENGTH];
 pub type HashFunc = sha1::Sha1;
-
-///
-/// not yet verified
-///
-pub fn hash<const KEY_BYTES: usize>(a: &BigNumber, b: &BigNumber) -> BigNumber {
-    HashFunc::new()
-        .chain(a.to_array_pad_zero::<KEY_BYTES>())
-        .chain(b.to_array_pad_zero::<KEY_BYTES>())
-        .into()
-}
+pub const HASH_NAME: &str = "SHA1";
 
 #[cfg(test)]
 mod tests {
     use std::convert::TryInto;
 
-    use super::*;
+    use crate::big_number::BigNumber;
 
     #[test]
     #[allow(non_snake_case)]
     /// u = H(A, B)
     fn should_hash_2_big_numbers() {
+        use crate::primitives::{calculate_u, PaddingPolicy};
+
         let A: BigNumber = "7BADE689AA63658C8DA684A78660BF1C62114269930D4141B9B30F75EDE466BB"
             .try_into()
             .unwrap();
@@ -34,10 +25,35 @@ mod tests {
             .try_into()
             .unwrap();
 
-        let u = hash::<32>(&A, &B);
+        let u = calculate_u::<32>(&A, &B, PaddingPolicy::default());
         let exp_hash: BigNumber = "DBC0E8AE033ACA9A9066E583DC160CB741A39737"
             .try_into()
             .unwrap();
         assert_eq!(&u, &exp_hash);
     }
+
+    #[test]
+    /// x = H(s | H(p)), the username is not part of it
+    fn should_hash_the_private_key_without_the_username() {
+        use crate::primitives::{calculate_private_key_x, IdentityHashing, Salt};
+
+        let s: Salt = "BEB25379D1A8581EB5A727673A2441EE".try_into().unwrap();
+        let x = calculate_private_key_x(
+            IdentityHashing::PasswordOnly,
+            b":",
+            "alice",
+            "password123",
+            &s,
+        );
+        let exp_x: BigNumber = "5EC09BC69705FD6A96975C2589C2AB5B77037F5C"
+            .try_into()
+            .unwrap();
+        assert_eq!(&x, &exp_x);
+
+        let x = calculate_private_key_x(IdentityHashing::Rfc2945, b":", "alice", "password123", &s);
+        let exp_x: BigNumber = "7E5250F2CB894FD9703611318C387A773FD52C09"
+            .try_into()
+            .unwrap();
+        assert_eq!(&x, &exp_x);
+    }
 }
@@ -14,13 +14,33 @@ This scheme is applied for all variables used in the calculus.
 [RFC2945]: https://datatracker.ietf.org/doc/html/rfc2945
 */
 use log::debug;
-use serde::Serialize;
-
-use crate::big_number::{BigNumber, Zero};
-use crate::hash::{hash, Digest, Hash, HashFunc, Update, HASH_LENGTH};
-use crate::{Result, Srp6Error};
-
-const STRONG_SESSION_KEY_LENGTH: usize = HASH_LENGTH * 2;
+use rand::{CryptoRng, RngCore};
+use serde::{Deserialize, Serialize};
+use std::convert::{TryFrom, TryInto};
+use std::fmt::{Debug, Display, Formatter};
+use subtle::ConstantTimeEq;
+
+use crate::big_number::{BigNumber, BigNumberError, Zero};
+use crate::hash::{Digest, Hash, HashFunc, Update, HASH_LENGTH};
+use crate::{GroupInfo, Result, Srp6Error};
+
+pub(crate) const STRONG_SESSION_KEY_LENGTH: usize = HASH_LENGTH * 2;
+/// the bytes between `I` and `p` in `H(I | ":" | p)` as in RFC2945
+pub(crate) const DEFAULT_IDENTITY_SEPARATOR: &[u8] = b":";
+/// a [`PasswordVerifier`] with less significant bits is flagged by [`PasswordVerifier::looks_weak`]
+pub const WEAK_VERIFIER_BITS: usize = 64;
+/// `g` is padded to this length in `k = H(N | PAD(g))`
+const GENERATOR_PAD_LENGTH: usize = 32;
+
+/// stands in for a secret in [`Debug`] output, e.g. `PrivateKey(<redacted>)`,
+/// so secrets do not end up in logs
+pub(crate) struct Redacted(pub(crate) &'static str);
+
+impl Debug for Redacted {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        write!(f, "{}(<redacted>)", self.0)
+    }
+}
 
 /// Refers to a large safe prime called `N` (`N = 2q+1`, where `q` is prime)
 #[doc(alias = "N")]
@@ -31,6 +51,10 @@ pub type PrimeModulus = BigNumber;
 pub type Generator = BigNumber;
 
 /// Refers to a User's salt called `s`
+///
+/// a salt is a number, it has no leading zeros to add or strip, it is padded to `SALT_LENGTH`
+/// where it is hashed as `PAD(s)`, so there is no conversion between salt lengths: to move to
+/// another `SALT_LENGTH` generate new user secrets, e.g. at the next password reset
 #[doc(alias = "s")]
 pub type Salt = BigNumber;
 
@@ -42,11 +66,80 @@ pub type PublicKey = BigNumber;
 #[doc(alias("a", "b"))]
 pub type PrivateKey = BigNumber;
 
+/// The users ephemeral [`PrivateKey`] `a`, a distinct type so it can not be mixed up with `b`
+#[derive(Clone, PartialEq, Serialize)]
+pub struct ClientPrivateKey(PrivateKey);
+
+/// The servers ephemeral [`PrivateKey`] `b`, a distinct type so it can not be mixed up with `a`
+#[derive(Clone, PartialEq, Serialize)]
+pub struct ServerPrivateKey(PrivateKey);
+
+impl Debug for ClientPrivateKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Redacted("ClientPrivateKey").fmt(f)
+    }
+}
+
+impl Debug for ServerPrivateKey {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Redacted("ServerPrivateKey").fmt(f)
+    }
+}
+
+/// the servers `b` with `g^b % N` already calculated, see [`crate::Srp6::precompute_ephemeral`]
+///
+/// not [`Clone`], so it is used for at most one handshake
+pub struct PrecomputedEphemeral {
+    pub(crate) b: PrivateKey,
+    pub(crate) g_b: PublicKey,
+}
+
+impl PrecomputedEphemeral {
+    pub(crate) fn new(b: PrivateKey, constants: &OpenConstants) -> Self {
+        let g_b = constants.generator.modpow(&b, &constants.module);
+
+        Self { b, g_b }
+    }
+}
+
+impl Debug for PrecomputedEphemeral {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Redacted("PrecomputedEphemeral").fmt(f)
+    }
+}
+
+impl ClientPrivateKey {
+    pub fn into_inner(self) -> PrivateKey {
+        self.0
+    }
+}
+
+impl From<PrivateKey> for ClientPrivateKey {
+    fn from(a: PrivateKey) -> Self {
+        Self(a)
+    }
+}
+
+impl ServerPrivateKey {
+    pub fn into_inner(self) -> PrivateKey {
+        self.0
+    }
+}
+
+impl From<PrivateKey> for ServerPrivateKey {
+    fn from(b: PrivateKey) -> Self {
+        Self(b)
+    }
+}
+
 /// Password Verifier is the users secret on the server side
 #[doc(alias = "v")]
 pub type PasswordVerifier = BigNumber;
 
 /// Refers to a multiplier parameter `k` (k = H(N, g) in SRP-6a, k = 3 for legacy SRP-6)
+///
+/// a custom `k` is created like any [`BigNumber`], e.g. `MultiplierParameter::from(3)`
+/// or [`MultiplierParameter::from_bytes_be`], and inspected by [`MultiplierParameter::to_bytes_be`]
 #[doc(alias = "k")]
 pub type MultiplierParameter = BigNumber;
 
@@ -60,9 +153,81 @@ pub type StrongSessionKey = BigNumber;
 /// Refers to `M` and `M1` Proof of server and client
 #[doc(alias("M", "M1"))]
 pub type Proof = BigNumber;
-/// Refers to `M2` the hash of Proof
+/// Refers to `M2` the hash of Proof, a distinct type so it can not be mixed up with the [`Proof`] `M1`
+///
+/// e.g. the host does not accept `M2` as the clients proof:
+/// ```compile_fail,E0308
+/// # use srp6::*;
+/// fn check(host: &mut Srp6<128, 128>, strong_proof: &StrongProof) {
+///     host.verify_proof(strong_proof);
+/// }
+/// ```
+/// and the client does not accept `M1` as the servers proof:
+/// ```compile_fail,E0308
+/// # use srp6::*;
+/// fn check(user: &mut Srp6User<128, 128>, proof: &Proof) {
+///     user.verify_proof(proof);
+/// }
+/// ```
 #[doc(alias = "M2")]
-pub type StrongProof = BigNumber;
+#[derive(Clone, Default, PartialEq, Serialize)]
+pub struct StrongProof(BigNumber);
+
+impl StrongProof {
+    /// `M2` from little endian bytes, e.g. as received from the host
+    pub fn from_bytes_le(bytes: &[u8]) -> Self {
+        Self(BigNumber::from_bytes_le(bytes))
+    }
+
+    /// `M2` from a hex string in big endian byte order, refused early if longer than a hash
+    pub fn from_hex_str_be(str: &str) -> std::result::Result<Self, BigNumberError> {
+        BigNumber::from_hex_str_be_bounded(str, HASH_LENGTH).map(Self)
+    }
+
+    /// `M2` in little endian byte order, padded by 0 for `N` bytes, e.g. to send it to the client
+    pub fn to_array_pad_zero<const N: usize>(&self) -> [u8; N] {
+        self.0.to_array_pad_zero::<N>()
+    }
+
+    /// `M2` as labeled text block `SRP STRONG PROOF`, see [`BigNumber::to_pem`]
+    #[cfg(feature = "pem")]
+    pub fn to_pem(&self) -> String {
+        self.0.to_pem(Self::PEM_LABEL)
+    }
+
+    /// reverse of [`StrongProof::to_pem`], refused if longer than a hash
+    #[cfg(feature = "pem")]
+    pub fn from_pem(pem: &str) -> std::result::Result<Self, BigNumberError> {
+        let strong_proof = BigNumber::from_pem(pem, Self::PEM_LABEL)?;
+        if strong_proof.num_bytes() > HASH_LENGTH {
+            return Err(BigNumberError::KeyLengthMismatch {
+                given: strong_proof.num_bytes(),
+                expected: HASH_LENGTH,
+            });
+        }
+
+        Ok(Self(strong_proof))
+    }
+
+    #[cfg(feature = "pem")]
+    const PEM_LABEL: &'static str = "SRP STRONG PROOF";
+
+    pub fn into_inner(self) -> BigNumber {
+        self.0
+    }
+}
+
+impl Debug for StrongProof {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Debug::fmt(&self.0, f)
+    }
+}
+
+impl Display for StrongProof {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
 
 /// Username `I` as [`String`]
 #[doc(alias = "I")]
@@ -73,37 +238,525 @@ pub type UsernameRef<'a> = &'a str;
 #[doc(alias = "p")]
 pub type ClearTextPassword = str;
 
-/// [`Username`] and [`ClearTextPassword`] used on the client side
-#[derive(Debug, Clone, Serialize)]
+/// [`Username`] and [`ClearTextPassword`] used on the client side,
+/// or on registration to derive the [`UserDetails`] via [`UserCredentials::into_user_details`]
+#[derive(Clone, Serialize)]
 pub struct UserCredentials<'a> {
     pub username: UsernameRef<'a>,
     pub password: &'a ClearTextPassword,
 }
 
-/// User details composes [`Username`], [`Salt`] and [`PasswordVerifier`] in one struct
-#[derive(Debug, Clone, Serialize)]
+/// User details composes [`Username`], [`Salt`] and [`PasswordVerifier`] in one struct,
+/// this is what the server stores instead of the password
+///
+/// with serde `salt` and `verifier` are upper case hex strings in big endian, e.g. for a
+/// document database, the lengths are checked against the group by [`Srp6::validate_user`](crate::Srp6::validate_user)
+/// or at the latest by [`HostAPI::continue_handshake`](crate::HostAPI::continue_handshake)
+#[derive(Clone, Serialize, Deserialize)]
 pub struct UserDetails {
     pub username: Username,
+    #[serde(with = "hex_be")]
     pub salt: Salt,
+    #[serde(with = "hex_be")]
     pub verifier: PasswordVerifier,
 }
 
-#[derive(Debug, Clone, Serialize)]
+/// a [`BigNumber`] as upper case hex string in big endian, a string for more than
+/// [`MAX_HEX_STR_BYTES`] bytes is refused before it is copied or decoded
+mod hex_be {
+    use serde::de::{Error, Visitor};
+    use serde::{Deserializer, Serializer};
+    use std::fmt::Formatter;
+
+    use crate::big_number::{BigNumber, Zero, MAX_HEX_STR_BYTES};
+
+    pub fn serialize<S: Serializer>(
+        n: &BigNumber,
+        serializer: S,
+    ) -> std::result::Result<S::Ok, S::Error> {
+        serializer.collect_str(n)
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(
+        deserializer: D,
+    ) -> std::result::Result<BigNumber, D::Error> {
+        deserializer.deserialize_str(HexVisitor)
+    }
+
+    struct HexVisitor;
+
+    impl Visitor<'_> for HexVisitor {
+        type Value = BigNumber;
+
+        fn expecting(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+            write!(f, "a hex string of at most {} bytes", MAX_HEX_STR_BYTES)
+        }
+
+        fn visit_str<E: Error>(self, hex: &str) -> std::result::Result<BigNumber, E> {
+            let n = BigNumber::from_hex_str_be_bounded(hex, MAX_HEX_STR_BYTES)
+                .map_err(Error::custom)?;
+            if n.is_zero() {
+                return Err(Error::custom("the number must not be zero"));
+            }
+
+            Ok(n)
+        }
+    }
+}
+
+impl Debug for UserCredentials<'_> {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UserCredentials")
+            .field("username", &self.username)
+            .field("password", &Redacted("ClearTextPassword"))
+            .finish()
+    }
+}
+
+impl Debug for UserDetails {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("UserDetails")
+            .field("username", &self.username)
+            .field("salt", &self.salt)
+            .field("verifier", &Redacted("PasswordVerifier"))
+            .finish()
+    }
+}
+
+/// `total length` and `len(I)` in [`UserDetails::to_registration_bytes`]
+const REGISTRATION_HEADER_LENGTH: usize = 4 + 2;
+
+impl UserDetails {
+    /// length of [`UserDetails::to_registration_bytes`] for a username of `username_len` bytes
+    pub const fn registration_len<const KEY_LENGTH: usize, const SALT_LENGTH: usize>(
+        username_len: usize,
+    ) -> usize {
+        REGISTRATION_HEADER_LENGTH + username_len + SALT_LENGTH + KEY_LENGTH + HASH_LENGTH
+    }
+
+    /// the total length of a registration upload, read from its first 4 bytes,
+    /// e.g. to check how much of a resumed upload is missing, `None` if less is received
+    pub fn registration_len_of(prefix: &[u8]) -> Option<usize> {
+        let total: [u8; 4] = prefix.get(..4)?.try_into().ok()?;
+
+        Some(u32::from_le_bytes(total) as usize)
+    }
+
+    /// frames the user details for the upload on registration, integers in little endian
+    /// `total length (u32) | len(I) (u16) | I | PAD(s) | PAD(v) | H(all before)`,
+    /// the hash lets the server detect a corrupted upload before it stores the verifier
+    ///
+    /// fails with [`Srp6Error::KeyLengthMismatch`] if `s` or `v` do not fit their length,
+    /// and with [`Srp6Error::InvalidUserDetails`] for a username longer than `u16::MAX` bytes
+    pub fn to_registration_bytes<const KEY_LENGTH: usize, const SALT_LENGTH: usize>(
+        &self,
+    ) -> Result<Vec<u8>> {
+        ensure_max_length(&self.salt, SALT_LENGTH)?;
+        ensure_max_length(&self.verifier, KEY_LENGTH)?;
+        let username_len = u16::try_from(self.username.len())
+            .map_err(|_| Srp6Error::InvalidUserDetails("username"))?;
+        let total = Self::registration_len::<KEY_LENGTH, SALT_LENGTH>(self.username.len());
+
+        let mut bytes = Vec::with_capacity(total);
+        bytes.extend_from_slice(&(total as u32).to_le_bytes());
+        bytes.extend_from_slice(&username_len.to_le_bytes());
+        bytes.extend_from_slice(self.username.as_bytes());
+        bytes.extend_from_slice(&self.salt.to_array_pad_zero::<SALT_LENGTH>());
+        bytes.extend_from_slice(&self.verifier.to_array_pad_zero::<KEY_LENGTH>());
+        let checksum = HashFunc::digest(&bytes);
+        bytes.extend_from_slice(&checksum);
+
+        Ok(bytes)
+    }
+
+    /// reverse of [`UserDetails::to_registration_bytes`]
+    /// - fails with [`Srp6Error::KeyLengthMismatch`] if the upload is incomplete or too long
+    /// - fails with [`Srp6Error::InvalidChecksum`] if the upload is corrupted
+    /// - fails with [`Srp6Error::InvalidUserDetails`] for an invalid username, an empty salt or verifier
+    pub fn from_registration_bytes<const KEY_LENGTH: usize, const SALT_LENGTH: usize>(
+        bytes: &[u8],
+    ) -> Result<Self> {
+        let header =
+            bytes
+                .get(..REGISTRATION_HEADER_LENGTH)
+                .ok_or(Srp6Error::KeyLengthMismatch {
+                    given: bytes.len(),
+                    expected: REGISTRATION_HEADER_LENGTH,
+                })?;
+        let username_len = u16::from_le_bytes([header[4], header[5]]) as usize;
+        let expected = Self::registration_len::<KEY_LENGTH, SALT_LENGTH>(username_len);
+        if bytes.len() != expected || Self::registration_len_of(bytes) != Some(expected) {
+            return Err(Srp6Error::KeyLengthMismatch {
+                given: bytes.len(),
+                expected,
+            });
+        }
+        let (framed, checksum) = bytes.split_at(expected - HASH_LENGTH);
+        if !bool::from(HashFunc::digest(framed)[..].ct_eq(checksum)) {
+            return Err(Srp6Error::InvalidChecksum);
+        }
+
+        let (username, rest) = framed[REGISTRATION_HEADER_LENGTH..].split_at(username_len);
+        let (salt, verifier) = rest.split_at(SALT_LENGTH);
+        let user_details = Self {
+            username: String::from_utf8(username.to_vec())
+                .map_err(|_| Srp6Error::InvalidUserDetails("username"))?,
+            salt: Salt::from_bytes_le(salt),
+            verifier: PasswordVerifier::from_bytes_le(verifier),
+        };
+        if user_details.salt.is_zero() {
+            return Err(Srp6Error::InvalidUserDetails("salt"));
+        }
+        if user_details.verifier.is_zero() {
+            return Err(Srp6Error::InvalidUserDetails("verifier"));
+        }
+
+        Ok(user_details)
+    }
+}
+
+/// [`Username`] and [`PublicKey`] `A` the client sends to start a handshake
+#[derive(Debug, Clone, PartialEq, Serialize)]
 pub struct UserHandshake {
     pub username: Username,
-    pub user_publickey: PublicKey
+    pub user_publickey: PublicKey,
 }
 
-#[derive(Debug, Clone, Serialize)]
-pub struct ServerHandshake{
+impl UserHandshake {
+    /// validates that `A` fits into `KEY_LENGTH` bytes, e.g. when received over the network
+    pub fn new<const KEY_LENGTH: usize>(
+        username: Username,
+        user_publickey: PublicKey,
+    ) -> Result<Self> {
+        ensure_max_length(&user_publickey, KEY_LENGTH)?;
+
+        Ok(Self {
+            username,
+            user_publickey,
+        })
+    }
+
+    pub fn username(&self) -> UsernameRef<'_> {
+        &self.username
+    }
+
+    pub fn user_publickey(&self) -> &PublicKey {
+        &self.user_publickey
+    }
+}
+
+/// [`Salt`] and [`PublicKey`] `B` the server answers a [`UserHandshake`] with
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ServerHandshake {
     pub salt: Salt,
-    pub server_publickey: PublicKey
+    pub server_publickey: PublicKey,
+}
+
+/// [`ServerHandshake`] without the [`Salt`], for protocols that send the salt separately
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ServerHandshakeNoSalt {
+    pub server_publickey: PublicKey,
+}
+
+impl ServerHandshake {
+    /// validates that `s` is not empty and fits into `SALT_LENGTH` and `B` into `KEY_LENGTH` bytes,
+    /// e.g. when received over the network
+    pub fn new<const KEY_LENGTH: usize, const SALT_LENGTH: usize>(
+        salt: Salt,
+        server_publickey: PublicKey,
+    ) -> Result<Self> {
+        ensure_non_empty_salt::<SALT_LENGTH>(&salt)?;
+        ensure_max_length(&salt, SALT_LENGTH)?;
+        ensure_max_length(&server_publickey, KEY_LENGTH)?;
+
+        Ok(Self {
+            salt,
+            server_publickey,
+        })
+    }
+
+    pub fn salt(&self) -> &Salt {
+        &self.salt
+    }
+
+    pub fn server_publickey(&self) -> &PublicKey {
+        &self.server_publickey
+    }
+
+    /// length of [`ServerHandshake::to_concat_bytes`], e.g. to allocate a buffer upfront
+    pub const fn serialized_len<const KEY_LENGTH: usize, const SALT_LENGTH: usize>() -> usize {
+        SALT_LENGTH + KEY_LENGTH
+    }
+
+    /// frames the handshake as one buffer `PAD(s) | PAD(B)`, both in little endian byte order
+    pub fn to_concat_bytes<const KEY_LENGTH: usize, const SALT_LENGTH: usize>(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Self::serialized_len::<KEY_LENGTH, SALT_LENGTH>());
+        bytes.extend_from_slice(&self.salt.to_array_pad_zero::<SALT_LENGTH>());
+        bytes.extend_from_slice(&self.server_publickey.to_array_pad_zero::<KEY_LENGTH>());
+
+        bytes
+    }
+
+    /// reverse of [`ServerHandshake::to_concat_bytes`], the buffer must be exactly
+    /// [`ServerHandshake::serialized_len`] bytes long
+    pub fn from_concat_bytes<const KEY_LENGTH: usize, const SALT_LENGTH: usize>(
+        bytes: &[u8],
+    ) -> Result<Self> {
+        let expected = Self::serialized_len::<KEY_LENGTH, SALT_LENGTH>();
+        if bytes.len() != expected {
+            return Err(Srp6Error::KeyLengthMismatch {
+                given: bytes.len(),
+                expected,
+            });
+        }
+        let (s, b) = bytes.split_at(SALT_LENGTH);
+
+        Ok(Self {
+            salt: Salt::from_bytes_le(s),
+            server_publickey: PublicKey::from_bytes_le(b),
+        })
+    }
+
+    /// drops the [`Salt`], the client must know it from a prior step
+    pub fn without_salt(self) -> ServerHandshakeNoSalt {
+        ServerHandshakeNoSalt {
+            server_publickey: self.server_publickey,
+        }
+    }
+}
+
+impl PasswordVerifier {
+    /// whether this verifier is `v = g^x % N` for the private key `x`, e.g. to check
+    /// verifiers generated in bulk without a handshake, compared in constant time
+    pub fn equals_g_pow_x(&self, constants: &OpenConstants, x: &PrivateKey) -> bool {
+        let expected = calculate_password_verifier_v(&constants.module, &constants.generator, x);
+        let len = constants.module.num_bytes();
+        // both are padded to the length of `N`, a longer `self` never matches
+        let pad = |n: &BigNumber| {
+            let mut bytes = n.to_vec();
+            bytes.resize(len.max(bytes.len()), 0);
+            bytes
+        };
+
+        pad(self).ct_eq(&pad(&expected)).into()
+    }
+
+    /// whether this verifier looks like the output of a broken client, e.g. of `x = 0` or `x = 1`:
+    /// it is `1`, `g` or has less than [`WEAK_VERIFIER_BITS`] significant bits
+    ///
+    /// such a verifier is cracked in no time, the account should be re-provisioned
+    pub fn looks_weak(&self, constants: &OpenConstants) -> bool {
+        self.num_bits() < WEAK_VERIFIER_BITS || self == &constants.generator
+    }
+}
+
+/// [`PublicKey`] `A` and [`Proof`] `M1` the client sends to finish a handshake
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct HandshakeProof {
+    pub user_publickey: PublicKey,
+    pub proof: Proof,
+}
+
+impl HandshakeProof {
+    /// validates that `A` fits into `KEY_LENGTH` and `M1` into the hash length
+    pub fn new<const KEY_LENGTH: usize>(user_publickey: PublicKey, proof: Proof) -> Result<Self> {
+        ensure_max_length(&user_publickey, KEY_LENGTH)?;
+        ensure_max_length(&proof, HASH_LENGTH)?;
+
+        Ok(Self {
+            user_publickey,
+            proof,
+        })
+    }
+
+    /// length of [`HandshakeProof::to_concat_bytes`], e.g. to allocate a buffer upfront
+    pub const fn serialized_len<const KEY_LENGTH: usize>() -> usize {
+        KEY_LENGTH + HASH_LENGTH
+    }
+
+    /// frames the proof as one buffer `PAD(A) | PAD(M1)`, both in little endian byte order
+    pub fn to_concat_bytes<const KEY_LENGTH: usize>(&self) -> Vec<u8> {
+        let mut bytes = Vec::with_capacity(Self::serialized_len::<KEY_LENGTH>());
+        bytes.extend_from_slice(&self.user_publickey.to_array_pad_zero::<KEY_LENGTH>());
+        bytes.extend_from_slice(&self.proof.to_array_pad_zero::<HASH_LENGTH>());
+
+        bytes
+    }
+
+    /// reverse of [`HandshakeProof::to_concat_bytes`], the buffer must be exactly
+    /// [`HandshakeProof::serialized_len`] bytes long
+    pub fn from_concat_bytes<const KEY_LENGTH: usize>(bytes: &[u8]) -> Result<Self> {
+        let expected = Self::serialized_len::<KEY_LENGTH>();
+        if bytes.len() != expected {
+            return Err(Srp6Error::KeyLengthMismatch {
+                given: bytes.len(),
+                expected,
+            });
+        }
+        let (a, m) = bytes.split_at(KEY_LENGTH);
+
+        Ok(Self {
+            user_publickey: PublicKey::from_bytes_le(a),
+            proof: Proof::from_bytes_le(m),
+        })
+    }
+}
+
+/// outcome of a verified handshake on the host side, see [`crate::HostAPI::verify_proof_with_session`]
+#[derive(Clone, Serialize)]
+pub struct VerifiedSession {
+    /// the user of the handshake, who proved to know the password
+    pub username: Username,
+    /// `M2`, to be sent to the client
+    pub strong_proof: StrongProof,
+    /// `K`, shared with the client
+    pub session_key: StrongSessionKey,
+    /// `A` exactly as used in the handshake, e.g. for audit logs or channel bindings
+    pub client_public_key: PublicKey,
+    /// the group of the handshake, e.g. to log the strength of the authentication
+    pub group: GroupInfo,
+}
+
+impl Debug for VerifiedSession {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        f.debug_struct("VerifiedSession")
+            .field("username", &self.username)
+            .field("strong_proof", &self.strong_proof)
+            .field("session_key", &Redacted("StrongSessionKey"))
+            .field("client_public_key", &self.client_public_key)
+            .field("group", &self.group)
+            .finish()
+    }
 }
 
 #[derive(Debug, Clone, Serialize)]
 pub struct OpenConstants {
     pub module: PrimeModulus,
-    pub generator: Generator
+    pub generator: Generator,
+}
+
+/// How the identity `I` goes into the private key `x`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub enum IdentityHashing {
+    /// `x = H(s | H(I | ":" | p))` as in [RFC2945](https://datatracker.ietf.org/doc/html/rfc2945)
+    #[default]
+    Rfc2945,
+    /// `x = H(s | H(p))`, the username is not part of `x` (legacy clients)
+    PasswordOnly,
+}
+
+/// How the session key `S` is serialized before it is hashed into `K`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default, Serialize)]
+pub enum SessionKeySerialization {
+    /// `S` padded with zeros to the length of `N`
+    #[default]
+    FixedWidth,
+    /// `S` without leading zero bytes, and without the first byte if the length is odd,
+    /// as the `SHA_Interleave` of [RFC2945](https://datatracker.ietf.org/doc/html/rfc2945#section-3.1)
+    Minimal,
+}
+
+/// Which parts go into the [`Proof`] `M1` and how, e.g. for WoW style servers
+///
+/// the default is [RFC2945](https://datatracker.ietf.org/doc/html/rfc2945#section-3):
+/// `M1 = H(H(N) xor H(g) | H(I) | s | A | B | K)`
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct ProofLayout {
+    /// `H(N) xor H(g)`, otherwise `H(N) | H(g)`
+    pub xor_group_hashes: bool,
+    /// whether `H(I)` is part of `M1` at all
+    pub include_identity: bool,
+    /// `H(upper(I))`, the identity in `x` is not affected
+    pub uppercase_identity: bool,
+}
+
+impl Default for ProofLayout {
+    fn default() -> Self {
+        Self {
+            xor_group_hashes: true,
+            include_identity: true,
+            uppercase_identity: false,
+        }
+    }
+}
+
+/// Which numbers are padded with zeros to their full length where they are hashed,
+/// e.g. for peers that hash `A` without leading zero bytes, all are padded by default
+///
+/// `x` always hashes `s` without leading zeros
+#[allow(non_snake_case)]
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+pub struct PaddingPolicy {
+    /// `PAD(A)` in `u`, `M1` and `M2`
+    pub pad_A: bool,
+    /// `PAD(B)` in `u` and `M1`
+    pub pad_B: bool,
+    /// `PAD(s)` in `M1`
+    pub pad_s: bool,
+    /// `PAD(g)` in `k = H(N | PAD(g))`
+    pub pad_g: bool,
+    /// `PAD(N)` to `KEY_LENGTH` bytes in `k` and in `H(N) xor H(g)` of `M1`,
+    /// only makes a difference for a group `N` with less than `KEY_LENGTH` bytes
+    pub pad_N: bool,
+}
+
+impl Default for PaddingPolicy {
+    fn default() -> Self {
+        Self {
+            pad_A: true,
+            pad_B: true,
+            pad_s: true,
+            pad_g: true,
+            pad_N: true,
+        }
+    }
+}
+
+/// `PAD(n)` to `LENGTH` bytes if `pad`, otherwise `n` without leading zeros
+fn pad_if<const LENGTH: usize>(n: &BigNumber, pad: bool) -> Vec<u8> {
+    if pad {
+        n.to_array_pad_zero::<LENGTH>().to_vec()
+    } else {
+        n.to_vec()
+    }
+}
+
+/// numbers are kept in minimal form, so they must not be longer than `expected` bytes
+pub(crate) fn ensure_max_length(n: &BigNumber, expected: usize) -> Result<()> {
+    if n.num_bytes() > expected {
+        return Err(Srp6Error::KeyLengthMismatch {
+            given: n.num_bytes(),
+            expected,
+        });
+    }
+
+    Ok(())
+}
+
+/// an empty [`Salt`], e.g. decoded from an empty string, would make `x` degenerate
+pub(crate) fn ensure_non_empty_salt<const SALT_LENGTH: usize>(s: &Salt) -> Result<()> {
+    if s.num_bytes() == 0 {
+        return Err(Srp6Error::KeyLengthMismatch {
+            given: 0,
+            expected: SALT_LENGTH,
+        });
+    }
+
+    Ok(())
+}
+
+/// the group must match `KEY_LENGTH`, otherwise padding would cut `N` or the keys
+pub(crate) fn ensure_group_length<const KEY_LENGTH: usize>(
+    constants: &OpenConstants,
+) -> Result<()> {
+    if constants.module.byte_len() != KEY_LENGTH {
+        return Err(Srp6Error::KeyLengthMismatch {
+            given: constants.module.byte_len(),
+            expected: KEY_LENGTH,
+        });
+    }
+
+    Ok(())
 }
 
 /// host version of a session key for a given user
@@ -119,18 +772,18 @@ pub(crate) fn calculate_session_key_S_for_host<const KEY_LENGTH: usize>(
     B: &PublicKey,
     b: &PrivateKey,
     v: &PasswordVerifier,
+    padding: PaddingPolicy,
 ) -> Result<SessionKey> {
     // safeguard A % N == 0 should be checked
     if (A % N).is_zero() {
         return Err(Srp6Error::InvalidPublicKey(A.clone()));
     }
 
-    let u = &calculate_u::<KEY_LENGTH>(A, B);
+    let u = &calculate_u::<KEY_LENGTH>(A, B, padding);
+    ensure_nonzero_u(u)?;
     let base = &(A * &v.modpow(u, N));
     let S: BigNumber = base.modpow(b, N);
 
-    debug!("S = {:?}", &S);
-
     Ok(S)
 }
 
@@ -142,24 +795,27 @@ pub(crate) fn calculate_session_key_S_for_host<const KEY_LENGTH: usize>(
 ///   - `S = (B - (k * g^x)) ^ (a + (u * x)) % N`
 ///   - `S = (B - (k * v)) ^ (a + (u * x)) % N`
 #[allow(non_snake_case)]
-#[allow(clippy::many_single_char_names)]
+#[allow(clippy::many_single_char_names, clippy::too_many_arguments)]
 pub(crate) fn calculate_session_key_S_for_client<const KEY_LENGTH: usize>(
     N: &PrimeModulus,
+    k: &MultiplierParameter,
     g: &Generator,
     B: &PublicKey,
     A: &PublicKey,
     a: &PrivateKey,
     x: &PrivateKey,
+    padding: PaddingPolicy,
 ) -> Result<SessionKey> {
     // safeguard B % N == 0
     if (B % N).is_zero() {
         return Err(Srp6Error::InvalidPublicKey(B.clone()));
     }
 
-    let u = &calculate_u::<KEY_LENGTH>(A, B);
+    let u = &calculate_u::<KEY_LENGTH>(A, B, padding);
+    ensure_nonzero_u(u)?;
     let exp: BigNumber = a + &(u * x);
     let g_mod_x = &g.modpow(x, N);
-    let to_sub = (&calculate_k(N, g) * g_mod_x) % N.clone();
+    let to_sub = (k * g_mod_x) % N.clone();
     // let base = B - ;
     let base = if B < &to_sub {
         &(N - &to_sub) + B
@@ -167,7 +823,6 @@ pub(crate) fn calculate_session_key_S_for_client<const KEY_LENGTH: usize>(
         B - &to_sub
     };
     let S = base.modpow(&exp, N);
-    debug!("S = {:?}", &S);
 
     Ok(S)
 }
@@ -178,8 +833,16 @@ pub(crate) fn calculate_session_key_S_for_client<const KEY_LENGTH: usize>(
 #[allow(non_snake_case)]
 pub(crate) fn calculate_session_key_hash_interleave_K<const KEY_LENGTH: usize>(
     S: &SessionKey,
+    serialization: SessionKeySerialization,
 ) -> StrongSessionKey {
-    let S = S.to_array_pad_zero::<KEY_LENGTH>();
+    let len = match serialization {
+        SessionKeySerialization::FixedWidth => KEY_LENGTH,
+        // `S < N` fits into `KEY_LENGTH` bytes, clamped anyway so slicing can not panic
+        SessionKeySerialization::Minimal => (S.num_bytes() & !1).min(KEY_LENGTH),
+    };
+    // little endian, so the leading bytes are cut off at the end
+    let padded = S.to_array_pad_zero::<KEY_LENGTH>();
+    let S = &padded[..len];
 
     // take the even bytes out of S
     let mut half = [0_u8; KEY_LENGTH];
@@ -187,14 +850,14 @@ pub(crate) fn calculate_session_key_hash_interleave_K<const KEY_LENGTH: usize>(
         half[i] = *Si;
     }
     // hash the even portion of S
-    let even_half_of_S_hash = HashFunc::new().chain(&half[..KEY_LENGTH / 2]).finalize();
+    let even_half_of_S_hash = HashFunc::new().chain(&half[..len / 2]).finalize();
 
     // take the odd bytes of S
     for (i, Si) in S.iter().skip(1).step_by(2).enumerate() {
         half[i] = *Si;
     }
     // hash the odd portion of S
-    let odd_half_of_S_hash = HashFunc::new().chain(&half[..KEY_LENGTH / 2]).finalize();
+    let odd_half_of_S_hash = HashFunc::new().chain(&half[..len / 2]).finalize();
 
     let mut vK = [0_u8; STRONG_SESSION_KEY_LENGTH];
     for (i, h_Si) in even_half_of_S_hash
@@ -206,13 +869,10 @@ pub(crate) fn calculate_session_key_hash_interleave_K<const KEY_LENGTH: usize>(
         vK[i * 2 + 1] = *h_Si.1;
     }
 
-    let K = BigNumber::from_bytes_le(&vK);
-    debug!("K = {:?}", &K);
-
-    K
+    BigNumber::from_bytes_le(&vK)
 }
 
-#[allow(non_snake_case)]
+#[allow(non_snake_case, clippy::too_many_arguments)]
 pub(crate) fn calculate_proof_M<const KEY_LENGTH: usize, const SALT_LENGTH: usize>(
     N: &PrimeModulus,
     g: &Generator,
@@ -221,23 +881,63 @@ pub(crate) fn calculate_proof_M<const KEY_LENGTH: usize, const SALT_LENGTH: usiz
     A: &PublicKey,
     B: &PublicKey,
     K: &StrongSessionKey,
+    layout: ProofLayout,
+    padding: PaddingPolicy,
 ) -> Proof {
-    let xor_hash: Hash = calculate_hash_N_xor_g::<KEY_LENGTH>(N, g);
-    let username_hash = HashFunc::new().chain(I.as_bytes()).finalize();
-    debug!("H(I) = {:?}", &username_hash);
-
-    let M: Proof = HashFunc::new()
-        .chain(xor_hash)
-        .chain(username_hash)
-        .chain(s.to_array_pad_zero::<SALT_LENGTH>())
-        .chain(A.to_array_pad_zero::<KEY_LENGTH>())
-        .chain(B.to_array_pad_zero::<KEY_LENGTH>())
-        .chain(K.to_array_pad_zero::<STRONG_SESSION_KEY_LENGTH>())
-        .into();
+    calculate_proof_M_with_salt_bytes::<KEY_LENGTH>(
+        N,
+        g,
+        I,
+        &pad_if::<SALT_LENGTH>(s, padding.pad_s),
+        A,
+        B,
+        K,
+        layout,
+        padding,
+    )
+}
 
-    debug!("M = {:?}", &M);
+/// same as [`calculate_proof_M`], but hashes the salt bytes as given instead of `PAD(s)`
+#[allow(non_snake_case, clippy::too_many_arguments)]
+pub(crate) fn calculate_proof_M_with_salt_bytes<const KEY_LENGTH: usize>(
+    N: &PrimeModulus,
+    g: &Generator,
+    I: UsernameRef,
+    s: &[u8],
+    A: &PublicKey,
+    B: &PublicKey,
+    K: &StrongSessionKey,
+    layout: ProofLayout,
+    padding: PaddingPolicy,
+) -> Proof {
+    let mut M = if layout.xor_group_hashes {
+        HashFunc::new().chain(calculate_hash_N_xor_g::<KEY_LENGTH>(N, g, padding.pad_N))
+    } else {
+        HashFunc::new()
+            .chain(
+                HashFunc::new()
+                    .chain(pad_if::<KEY_LENGTH>(N, padding.pad_N))
+                    .finalize(),
+            )
+            .chain(HashFunc::new().chain(g.to_vec().as_slice()).finalize())
+    };
+    if layout.include_identity {
+        let username_hash = if layout.uppercase_identity {
+            HashFunc::new()
+                .chain(I.to_uppercase().as_bytes())
+                .finalize()
+        } else {
+            HashFunc::new().chain(I.as_bytes()).finalize()
+        };
+        debug!("H(I) = {:?}", &username_hash);
+        M = M.chain(username_hash);
+    }
 
-    M
+    M.chain(s)
+        .chain(pad_if::<KEY_LENGTH>(A, padding.pad_A))
+        .chain(pad_if::<KEY_LENGTH>(B, padding.pad_B))
+        .chain(K.to_array_pad_zero::<STRONG_SESSION_KEY_LENGTH>())
+        .into()
 }
 
 /// todo(verify): check if padding is needed or not
@@ -247,17 +947,37 @@ pub(crate) fn calculate_strong_proof_M2<const KEY_LENGTH: usize>(
     A: &PublicKey,
     M: &Proof,
     K: &StrongSessionKey,
+    padding: PaddingPolicy,
 ) -> StrongProof {
-    let M2: StrongProof = HashFunc::new()
-        .chain(A.to_array_pad_zero::<KEY_LENGTH>())
-        .chain(M.to_array_pad_zero::<HASH_LENGTH>())
-        .chain(K.to_array_pad_zero::<STRONG_SESSION_KEY_LENGTH>())
-        .into();
+    let M2 = StrongProof(
+        HashFunc::new()
+            .chain(pad_if::<KEY_LENGTH>(A, padding.pad_A))
+            .chain(M.to_array_pad_zero::<HASH_LENGTH>())
+            .chain(K.to_array_pad_zero::<STRONG_SESSION_KEY_LENGTH>())
+            .into(),
+    );
     debug!("M2 = {:?}", &M2);
 
     M2
 }
 
+/// compares a given proof (`M`, `M2`) with the expected one in constant time
+///
+/// a given proof that is longer than a hash never matches
+pub(crate) fn proofs_match(expected: &Proof, given: &Proof) -> bool {
+    let fits = given.num_bytes() <= HASH_LENGTH;
+    let equal: bool = expected.to_array_pad_zero::<HASH_LENGTH>()[..]
+        .ct_eq(&given.to_array_pad_zero::<HASH_LENGTH>()[..])
+        .into();
+
+    fits & equal
+}
+
+/// [`proofs_match`] for the [`StrongProof`] `M2`
+pub(crate) fn strong_proofs_match(expected: &StrongProof, given: &StrongProof) -> bool {
+    proofs_match(&expected.0, &given.0)
+}
+
 /// here we hash g and xor it with the hash of N
 ///
 /// ```plain
@@ -266,9 +986,14 @@ pub(crate) fn calculate_strong_proof_M2<const KEY_LENGTH: usize>(
 ///                    // this portion is calculated here
 /// ```
 #[allow(non_snake_case)]
-fn calculate_hash_N_xor_g<const KEY_LENGTH: usize>(N: &PrimeModulus, g: &Generator) -> Hash {
+fn calculate_hash_N_xor_g<const KEY_LENGTH: usize>(
+    N: &PrimeModulus,
+    g: &Generator,
+    pad_N: bool,
+) -> Hash {
+    debug_assert!(N.byte_len() <= KEY_LENGTH, "N is longer than KEY_LENGTH");
     let mut h = HashFunc::new()
-        .chain(N.to_array_pad_zero::<KEY_LENGTH>())
+        .chain(pad_if::<KEY_LENGTH>(N, pad_N))
         .finalize();
     let h_g = HashFunc::new().chain(g.to_vec().as_slice()).finalize();
     for (i, v) in h.iter_mut().enumerate() {
@@ -300,13 +1025,29 @@ pub(crate) fn calculate_password_verifier_v(
 /// `u` is the hash of host's and client's [`PublicKey`]
 /// formula: `H(PAD(A) | PAD(B))`
 #[allow(non_snake_case)]
-pub(crate) fn calculate_u<const KEY_LENGTH: usize>(A: &PublicKey, B: &PublicKey) -> BigNumber {
-    let u = hash::<KEY_LENGTH>(A, B);
+pub(crate) fn calculate_u<const KEY_LENGTH: usize>(
+    A: &PublicKey,
+    B: &PublicKey,
+    padding: PaddingPolicy,
+) -> BigNumber {
+    let u: BigNumber = HashFunc::new()
+        .chain(pad_if::<KEY_LENGTH>(A, padding.pad_A))
+        .chain(pad_if::<KEY_LENGTH>(B, padding.pad_B))
+        .into();
     debug!("u = {:?}", &u);
 
     u
 }
 
+/// the protocol must abort if `u` is zero, it can only be with a broken hash
+pub(crate) fn ensure_nonzero_u(u: &BigNumber) -> Result<()> {
+    if u.is_zero() {
+        return Err(Srp6Error::ZeroScalar);
+    }
+
+    Ok(())
+}
+
 /// `A` is the [`PublicKey`] of the client
 /// formula: `A = g^a % N`
 #[allow(non_snake_case)]
@@ -319,15 +1060,28 @@ pub(crate) fn calculate_pubkey_A(N: &PrimeModulus, g: &Generator, a: &PrivateKey
 
 /// [`PublicKey`][B] is the hosts public key
 /// `B = kv + g^b`
-#[allow(non_snake_case)]
+#[allow(non_snake_case, dead_code)]
 pub(crate) fn calculate_pubkey_B(
     N: &PrimeModulus,
+    k: &MultiplierParameter,
     g: &Generator,
     v: &PasswordVerifier,
     b: &PrivateKey,
 ) -> PublicKey {
     let g_mod_N = g.modpow(b, N);
-    let B = &((&calculate_k(&N, &g) * v) + g_mod_N) % N;
+
+    calculate_pubkey_B_from_g_b(N, k, v, &g_mod_N)
+}
+
+/// same as [`calculate_pubkey_B`] with `g^b % N` already calculated
+#[allow(non_snake_case)]
+pub(crate) fn calculate_pubkey_B_from_g_b(
+    N: &PrimeModulus,
+    k: &MultiplierParameter,
+    v: &PasswordVerifier,
+    g_b: &PublicKey,
+) -> PublicKey {
+    let B = &(&(k * v) + g_b) % N;
     debug!("B = {:?}", &B);
 
     B
@@ -339,58 +1093,94 @@ pub(crate) fn calculate_pubkey_B(
 /// p:  Cleartext Password      (is uppercased for WoW)
 /// s:  User's salt
 /// x:  Private key (derived from p and s)
-/// ph = H(I, ':', p)           (':' is a string literal)
+/// ph = H(I, ':', p)           (':' by default, see [`IdentityHashing`] and `with_identity_separator`)
 /// x = H(s, ph)                (s is chosen randomly)
 #[allow(non_snake_case)]
-#[allow(dead_code)]
 pub(crate) fn calculate_private_key_x(
+    identity: IdentityHashing,
+    separator: &[u8],
     I: UsernameRef,
     p: &ClearTextPassword,
     s: &Salt,
 ) -> PrivateKey {
-    let ph = calculate_p_hash(I, p);
-    let x: PrivateKey = HashFunc::new()
+    let ph = calculate_p_hash(identity, separator, I, p);
+    HashFunc::new()
         .chain(s.to_vec().as_slice())
         .chain(ph)
-        .into();
-    debug!("x = {:?}", &x);
-
-    x
+        .into()
 }
 
 /// hashes the user and the password (used for client private key `x`)
 #[allow(non_snake_case)]
-pub(crate) fn calculate_p_hash(I: UsernameRef, p: &ClearTextPassword) -> Hash {
-    HashFunc::new()
-        .chain(I.as_bytes())
-        .chain(":".as_bytes())
-        .chain(p.as_bytes())
-        .finalize()
-        .into()
+pub(crate) fn calculate_p_hash(
+    identity: IdentityHashing,
+    separator: &[u8],
+    I: UsernameRef,
+    p: &ClearTextPassword,
+) -> Hash {
+    match identity {
+        IdentityHashing::Rfc2945 => HashFunc::new()
+            .chain(I.as_bytes())
+            .chain(separator)
+            .chain(p.as_bytes())
+            .finalize()
+            .into(),
+        IdentityHashing::PasswordOnly => HashFunc::new().chain(p.as_bytes()).finalize().into(),
+    }
 }
 
 /// `k = H(N | PAD(g))` (k = 3 for legacy SRP-6)
+///
+/// `g` is padded to at least 32 bytes, a longer generator of a custom group is hashed in full,
+/// unless [`PaddingPolicy::pad_g`] is off, `N` is padded to `KEY_LENGTH` unless
+/// [`PaddingPolicy::pad_N`] is off
 #[allow(non_snake_case)]
-pub(crate) fn calculate_k(
+pub(crate) fn calculate_k<const KEY_LENGTH: usize>(
     N: &PrimeModulus,
     g: &Generator,
+    padding: PaddingPolicy,
 ) -> MultiplierParameter {
+    let mut padded_g = g.to_vec();
+    if padding.pad_g && padded_g.len() < GENERATOR_PAD_LENGTH {
+        padded_g.resize(GENERATOR_PAD_LENGTH, 0);
+    }
+
     HashFunc::new()
-        .chain(N.to_vec().as_slice())
-        .chain(g.to_array_pad_zero::<32>())
+        .chain(pad_if::<KEY_LENGTH>(N, padding.pad_N))
+        .chain(padded_g.as_slice())
         .into()
 }
 
+/// a custom `k` if given, otherwise `k = H(N | PAD(g))`
+pub(crate) fn multiplier_or_default<const KEY_LENGTH: usize>(
+    custom: Option<&MultiplierParameter>,
+    constants: &OpenConstants,
+    padding: PaddingPolicy,
+) -> MultiplierParameter {
+    match custom {
+        Some(k) => k.clone(),
+        None => calculate_k::<KEY_LENGTH>(&constants.module, &constants.generator, padding),
+    }
+}
+
 /// [`PrivateKey`] `a` or `b` is in fact just a big (positive) random number
 pub(crate) fn generate_private_key<const KEY_LENGTH: usize>() -> PrivateKey {
     PrivateKey::new_rand(KEY_LENGTH)
 }
 
 /// [`Salt`] `s` is a random number
+#[allow(dead_code)]
 pub(crate) fn generate_salt<const SALT_LENGTH: usize>() -> Salt {
     Salt::new_rand(SALT_LENGTH)
 }
 
+/// [`Salt`] `s` is a random number drawn from `rng`
+pub(crate) fn generate_salt_with_rng<const SALT_LENGTH: usize, R: RngCore + CryptoRng>(
+    rng: &mut R,
+) -> Salt {
+    Salt::new_rand_with(rng, SALT_LENGTH)
+}
+
 #[cfg(test)]
 #[cfg(feature = "legacy")]
 mod tests {
@@ -438,6 +1228,7 @@ mod tests {
             &Mock::B(),
             &Mock::b(),
             &Mock::v(),
+            PaddingPolicy::default(),
         )
         .unwrap();
 
@@ -455,13 +1246,17 @@ mod tests {
             &Mock::B(),
             &Mock::b(),
             &Mock::v(),
+            PaddingPolicy::default(),
         )
         .unwrap();
     }
 
     #[test]
     fn should_calculate_hash_of_a_session_key() {
-        let hash_of_session_key = calculate_session_key_hash_interleave_K::<KEY_LENGTH>(&Mock::S());
+        let hash_of_session_key = calculate_session_key_hash_interleave_K::<KEY_LENGTH>(
+            &Mock::S(),
+            SessionKeySerialization::default(),
+        );
         assert_eq!(&hash_of_session_key, &Mock::K())
     }
 
@@ -470,7 +1265,7 @@ mod tests {
         use hex_literal::hex;
 
         let params = Srp6_256::default();
-        let h = calculate_hash_N_xor_g::<KEY_LENGTH>(&params.N, &params.g);
+        let h = calculate_hash_N_xor_g::<KEY_LENGTH>(&params.N, &params.g, true);
         const EXPECTED_HASH_LE: Hash =
             hex!("DD 7B B0 3A 38 AC 73 11 03 98 7C 5A 50 6F CA 96 6C 7B C2 A7");
         // both are equivalent, here it's the big endian hex string representation
@@ -495,6 +1290,8 @@ mod tests {
             &Mock::A(),
             &Mock::B(),
             &Mock::K(),
+            ProofLayout::default(),
+            PaddingPolicy::default(),
         );
 
         assert_eq!(&proof_m, &Mock::M())
@@ -0,0 +1,185 @@
+/*!
+Prometheus style metrics behind the `metrics` feature, recorded via the [`metrics`] facade,
+the application installs a recorder / exporter of its choice.
+
+- `srp_handshakes_total{side}`: handshakes started, by [`crate::HostAPI::continue_handshake`]
+  on the host and [`crate::UserTrait::start_handshake`] on the client
+- `srp_verifications_total{side, result}`: proofs checked, `result` is `ok` or `invalid`
+- `srp_verification_duration_seconds{side}`: how long checking a proof took
+
+`side` is `host` or `client`. The labels never contain secrets or usernames.
+Without the feature nothing is recorded and no time is taken.
+*/
+#[cfg(feature = "metrics")]
+use std::time::Instant;
+
+use crate::Result;
+
+#[cfg(feature = "metrics")]
+const HANDSHAKES_TOTAL: &str = "srp_handshakes_total";
+#[cfg(feature = "metrics")]
+const VERIFICATIONS_TOTAL: &str = "srp_verifications_total";
+#[cfg(feature = "metrics")]
+const VERIFICATION_DURATION: &str = "srp_verification_duration_seconds";
+
+#[derive(Debug, Clone, Copy)]
+pub(crate) enum Side {
+    Host,
+    Client,
+}
+
+#[cfg(feature = "metrics")]
+impl Side {
+    fn label(self) -> &'static str {
+        match self {
+            Side::Host => "host",
+            Side::Client => "client",
+        }
+    }
+}
+
+/// counts a started handshake
+#[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+pub(crate) fn handshake_started(side: Side) {
+    #[cfg(feature = "metrics")]
+    metrics::counter!(HANDSHAKES_TOTAL, "side" => side.label()).increment(1);
+}
+
+/// measures one proof check, from [`Verification::start`] to [`Verification::finish`]
+pub(crate) struct Verification {
+    #[cfg(feature = "metrics")]
+    side: Side,
+    #[cfg(feature = "metrics")]
+    started: Instant,
+}
+
+impl Verification {
+    #[cfg_attr(not(feature = "metrics"), allow(unused_variables))]
+    pub(crate) fn start(side: Side) -> Self {
+        Self {
+            #[cfg(feature = "metrics")]
+            side,
+            #[cfg(feature = "metrics")]
+            started: Instant::now(),
+        }
+    }
+
+    /// records the outcome of the check, and hands the `result` through
+    pub(crate) fn finish<T>(self, result: Result<T>) -> Result<T> {
+        #[cfg(feature = "metrics")]
+        {
+            let outcome = if result.is_ok() { "ok" } else { "invalid" };
+            let side = self.side.label();
+            metrics::counter!(VERIFICATIONS_TOTAL, "side" => side, "result" => outcome)
+                .increment(1);
+            metrics::histogram!(VERIFICATION_DURATION, "side" => side)
+                .record(self.started.elapsed());
+        }
+
+        result
+    }
+}
+
+#[cfg(test)]
+#[cfg(all(feature = "metrics", feature = "group-1024"))]
+mod tests {
+    use crate::{rfc5054_1024, HostAPI, Proof, Srp6, Srp6User, UserTrait};
+    use metrics::{
+        Counter, CounterFn, Gauge, Histogram, HistogramFn, Key, KeyName, Metadata, Recorder,
+        SharedString, Unit,
+    };
+    use std::collections::BTreeMap;
+    use std::sync::{Arc, Mutex};
+
+    /// counts every increment and recorded value per `name{labels}`
+    #[derive(Default)]
+    struct CountingRecorder(Arc<Mutex<BTreeMap<String, u64>>>);
+
+    struct Handle(Arc<Mutex<BTreeMap<String, u64>>>, String);
+
+    impl CounterFn for Handle {
+        fn increment(&self, value: u64) {
+            *self.0.lock().unwrap().entry(self.1.clone()).or_insert(0) += value;
+        }
+
+        fn absolute(&self, _: u64) {}
+    }
+
+    impl HistogramFn for Handle {
+        fn record(&self, _: f64) {
+            *self.0.lock().unwrap().entry(self.1.clone()).or_insert(0) += 1;
+        }
+    }
+
+    impl CountingRecorder {
+        fn handle(&self, key: &Key) -> Arc<Handle> {
+            let labels: Vec<String> = key
+                .labels()
+                .map(|l| format!("{}={}", l.key(), l.value()))
+                .collect();
+            let name = format!("{}{{{}}}", key.name(), labels.join(","));
+
+            Arc::new(Handle(self.0.clone(), name))
+        }
+
+        fn count(&self, name: &str) -> u64 {
+            self.0.lock().unwrap().get(name).copied().unwrap_or(0)
+        }
+    }
+
+    impl Recorder for CountingRecorder {
+        fn describe_counter(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_gauge(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+        fn describe_histogram(&self, _: KeyName, _: Option<Unit>, _: SharedString) {}
+
+        fn register_counter(&self, key: &Key, _: &Metadata<'_>) -> Counter {
+            Counter::from_arc(self.handle(key))
+        }
+
+        fn register_gauge(&self, _: &Key, _: &Metadata<'_>) -> Gauge {
+            Gauge::noop()
+        }
+
+        fn register_histogram(&self, key: &Key, _: &Metadata<'_>) -> Histogram {
+            Histogram::from_arc(self.handle(key))
+        }
+    }
+
+    #[test]
+    fn should_count_handshakes_and_verifications() {
+        let recorder = CountingRecorder::default();
+        metrics::with_local_recorder(&recorder, || {
+            let constants = rfc5054_1024();
+            let mut host = Srp6::<128, 128>::new();
+            let mut user = Srp6User::<128, 128>::new();
+            let details = host.generate_new_user_secrets("Bob", "secret-password", &constants);
+            let user_handshake = user.start_handshake("Bob", &constants);
+            let server_handshake = host
+                .continue_handshake(&details, &user_handshake, &constants)
+                .unwrap();
+            let proof = user
+                .update_handshake(&server_handshake, &constants, "Bob", "secret-password")
+                .unwrap();
+
+            assert!(host.verify_proof(&Proof::from(7)).is_err());
+            let strong_proof = host.verify_proof(&proof).unwrap();
+            assert!(user.verify_proof(&strong_proof).is_ok());
+        });
+
+        assert_eq!(recorder.count("srp_handshakes_total{side=host}"), 1);
+        assert_eq!(recorder.count("srp_handshakes_total{side=client}"), 1);
+        for (side, ok, invalid) in [("host", 1, 1), ("client", 1, 0)].iter() {
+            let total =
+                |result| format!("srp_verifications_total{{side={},result={}}}", side, result);
+            assert_eq!(recorder.count(&total("ok")), *ok);
+            assert_eq!(recorder.count(&total("invalid")), *invalid);
+            assert_eq!(
+                recorder.count(&format!(
+                    "srp_verification_duration_seconds{{side={}}}",
+                    side
+                )),
+                ok + invalid
+            );
+        }
+    }
+}
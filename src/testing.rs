@@ -0,0 +1,352 @@
+/*!
+Helpers for tests and reviews, behind the `testing` feature, not for production.
+
+[`conformance_report`] runs one exchange for fixed inputs and reports every intermediate
+value as upper case hex, e.g. to diff it against the [RFC5054] test vectors in CI.
+With the `timing-test` feature `timing_check` compares the time to reject a wrong
+proof with the time to reject a malformed one, as smoke test of the constant time check,
+and `dispatch_timing_check` compares the handshake of a known user with the one of an
+unknown user answered by a dummy.
+
+**NOTE**: this crate hashes numbers in little endian byte order, the RFC in big endian,
+so `k`, `x`, `u`, `M1`, `M2` and everything derived from them differ from the RFC values.
+
+[RFC5054]: https://datatracker.ietf.org/doc/html/rfc5054#appendix-B
+*/
+use serde::Serialize;
+#[cfg(feature = "timing-test")]
+use std::time::Duration;
+
+use crate::primitives::*;
+use crate::Result;
+
+/// the fixed inputs of an exchange, including both private keys
+#[derive(Debug, Clone)]
+pub struct ConformanceInputs {
+    pub username: Username,
+    pub password: String,
+    pub salt: Salt,
+    pub a: PrivateKey,
+    pub b: PrivateKey,
+    pub constants: OpenConstants,
+}
+
+impl ConformanceInputs {
+    /// the inputs of the test vectors in [RFC5054] Appendix B
+    ///
+    /// [RFC5054]: https://datatracker.ietf.org/doc/html/rfc5054#appendix-B
+    #[cfg(feature = "group-1024")]
+    pub fn rfc5054_appendix_b() -> Self {
+        let hex = |s: &str| PrivateKey::from_hex_str_be(s).expect("valid hex");
+
+        Self {
+            username: "alice".to_owned(),
+            password: "password123".to_owned(),
+            salt: hex("BEB25379D1A8581EB5A727673A2441EE"),
+            a: hex("60975527035CF2AD1989806F0407210BC81EDC04E2762A56AFD529DDDA2D4393"),
+            b: hex("E487CB59D31AC550471E81F00F6928E01DDA08E974A004F49E61F5D105284D20"),
+            constants: crate::rfc5054_1024(),
+        }
+    }
+}
+
+/// every intermediate value of an exchange as upper case hex, see [`conformance_report`]
+#[allow(non_snake_case)]
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ConformanceReport {
+    pub k: String,
+    pub x: String,
+    pub v: String,
+    pub A: String,
+    pub B: String,
+    pub u: String,
+    pub S: String,
+    pub K: String,
+    pub M1: String,
+    pub M2: String,
+}
+
+/// runs the exchange for `inputs` with the defaults of [`crate::Srp6`],
+/// fails if the calculation does, e.g. for an invalid public key
+#[allow(non_snake_case)]
+pub fn conformance_report<const KEY_LENGTH: usize, const SALT_LENGTH: usize>(
+    inputs: &ConformanceInputs,
+) -> Result<ConformanceReport> {
+    let N = &inputs.constants.module;
+    let g = &inputs.constants.generator;
+    let I = inputs.username.as_str();
+
+    let k = calculate_k::<KEY_LENGTH>(N, g, PaddingPolicy::default());
+    let x = calculate_private_key_x(
+        IdentityHashing::default(),
+        DEFAULT_IDENTITY_SEPARATOR,
+        I,
+        &inputs.password,
+        &inputs.salt,
+    );
+    let v = calculate_password_verifier_v(N, g, &x);
+    let A = calculate_pubkey_A(N, g, &inputs.a);
+    let B = calculate_pubkey_B(N, &k, g, &v, &inputs.b);
+    let u = calculate_u::<KEY_LENGTH>(&A, &B, PaddingPolicy::default());
+    let S = calculate_session_key_S_for_host::<KEY_LENGTH>(
+        N,
+        &A,
+        &B,
+        &inputs.b,
+        &v,
+        PaddingPolicy::default(),
+    )?;
+    let K = calculate_session_key_hash_interleave_K::<KEY_LENGTH>(
+        &S,
+        SessionKeySerialization::default(),
+    );
+    let M1 = calculate_proof_M::<KEY_LENGTH, SALT_LENGTH>(
+        N,
+        g,
+        I,
+        &inputs.salt,
+        &A,
+        &B,
+        &K,
+        ProofLayout::default(),
+        PaddingPolicy::default(),
+    );
+    let M2 = calculate_strong_proof_M2::<KEY_LENGTH>(&A, &M1, &K, PaddingPolicy::default());
+
+    Ok(ConformanceReport {
+        k: k.to_string(),
+        x: x.to_string(),
+        v: v.to_string(),
+        A: A.to_string(),
+        B: B.to_string(),
+        u: u.to_string(),
+        S: S.to_string(),
+        K: K.to_string(),
+        M1: M1.to_string(),
+        M2: M2.to_string(),
+    })
+}
+
+/// the medians of [`timing_check`], as measured on this machine
+#[cfg(feature = "timing-test")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimingReport {
+    pub iterations: usize,
+    /// the median time to reject a well formed proof of a wrong password
+    pub wrong_password: Duration,
+    /// the median time to reject a proof longer than a hash
+    pub malformed: Duration,
+}
+
+#[cfg(feature = "timing-test")]
+impl TimingReport {
+    /// the slower median divided by the faster one, `1.0` for identical timings
+    pub fn ratio(&self) -> f64 {
+        ratio(self.wrong_password, self.malformed)
+    }
+
+    /// whether the medians differ by at most `tolerance`, e.g. `0.5` for 50%
+    pub fn is_within(&self, tolerance: f64) -> bool {
+        self.ratio() <= 1.0 + tolerance
+    }
+}
+
+/// smoke test of the constant time proof check, **not** a proof of constant time behavior
+///
+/// measures [`HostAPI::verify_proof`](crate::HostAPI::verify_proof) `iterations` times for
+/// a proof of a wrong password and for a malformed proof, interleaved, so drift of the
+/// machine hits both alike, and reports the medians. Timings on shared CI machines are
+/// noisy, compare them with a generous tolerance, see [`TimingReport::is_within`]
+#[cfg(feature = "timing-test")]
+pub fn timing_check(iterations: usize) -> TimingReport {
+    use crate::hash::HASH_LENGTH;
+    use crate::{HostAPI, Srp6, Srp6User, UserTrait};
+    use std::time::Instant;
+
+    let constants = crate::rfc5054_1024();
+    let mut host = Srp6::<128, 16>::new();
+    let mut user = Srp6User::<128, 16>::new();
+    let details = host.generate_new_user_secrets("Bob", "secret-password", &constants);
+    let user_handshake = user.start_handshake("Bob", &constants);
+    let server_handshake = host
+        .continue_handshake(&details, &user_handshake, &constants)
+        .expect("a valid handshake");
+    let wrong_password = user
+        .update_handshake(&server_handshake, &constants, "Bob", "wrong-password")
+        .expect("a valid handshake");
+    let malformed = Proof::from_bytes_le(&[0xFF; 2 * HASH_LENGTH]);
+
+    let mut measure = |proof: &Proof| {
+        let start = Instant::now();
+        let result = host.verify_proof(proof);
+        let elapsed = start.elapsed();
+        assert!(result.is_err(), "the proof must be rejected");
+        elapsed
+    };
+    let (mut wrong_password_times, mut malformed_times) = (
+        Vec::with_capacity(iterations),
+        Vec::with_capacity(iterations),
+    );
+    for _ in 0..iterations {
+        wrong_password_times.push(measure(&wrong_password));
+        malformed_times.push(measure(&malformed));
+    }
+
+    TimingReport {
+        iterations,
+        wrong_password: median(wrong_password_times),
+        malformed: median(malformed_times),
+    }
+}
+
+/// the medians of [`dispatch_timing_check`], as measured on this machine
+#[cfg(feature = "timing-test")]
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DispatchTimingReport {
+    pub iterations: usize,
+    /// the median time to look up a known user and continue the handshake
+    pub known_user: Duration,
+    /// the median time to answer an unknown user with a dummy and continue the handshake
+    pub unknown_user: Duration,
+}
+
+#[cfg(feature = "timing-test")]
+impl DispatchTimingReport {
+    /// the slower median divided by the faster one, `1.0` for identical timings
+    pub fn ratio(&self) -> f64 {
+        ratio(self.known_user, self.unknown_user)
+    }
+
+    /// whether the medians differ by at most `tolerance`, e.g. `0.5` for 50%
+    pub fn is_within(&self, tolerance: f64) -> bool {
+        self.ratio() <= 1.0 + tolerance
+    }
+}
+
+/// smoke test of the dispatch between known and unknown users, **not** a proof of constant
+/// time behavior
+///
+/// measures [`Srp6::user_details_or_dummy`](crate::Srp6::user_details_or_dummy) followed by
+/// [`HostAPI::continue_handshake`](crate::HostAPI::continue_handshake) `iterations` times for
+/// a known and for an unknown user, interleaved, and reports the medians, see [`timing_check`]
+#[cfg(feature = "timing-test")]
+pub fn dispatch_timing_check(iterations: usize) -> DispatchTimingReport {
+    use crate::{HostAPI, Srp6, Srp6User, UserTrait};
+    use std::time::Instant;
+
+    const SERVER_SECRET: &[u8] = b"dispatch timing check";
+
+    let constants = crate::rfc5054_1024();
+    let details =
+        Srp6::<128, 16>::new().generate_new_user_secrets("Bob", "secret-password", &constants);
+    let lookup = |username: UsernameRef| {
+        (username == details.username).then(|| (details.salt.clone(), details.verifier.clone()))
+    };
+    let mut user = Srp6User::<128, 16>::new();
+    let known = user.start_handshake("Bob", &constants);
+    let unknown = UserHandshake {
+        username: "Eve".to_owned(),
+        user_publickey: known.user_publickey.clone(),
+    };
+
+    let measure = |user_handshake: &UserHandshake| {
+        let start = Instant::now();
+        let user_details = Srp6::<128, 16>::user_details_or_dummy(
+            &user_handshake.username,
+            lookup,
+            SERVER_SECRET,
+            &constants,
+        );
+        let result =
+            Srp6::<128, 16>::new().continue_handshake(&user_details, user_handshake, &constants);
+        let elapsed = start.elapsed();
+        assert!(result.is_ok(), "both users must get a handshake");
+        elapsed
+    };
+    let (mut known_user_times, mut unknown_user_times) = (
+        Vec::with_capacity(iterations),
+        Vec::with_capacity(iterations),
+    );
+    for _ in 0..iterations {
+        known_user_times.push(measure(&known));
+        unknown_user_times.push(measure(&unknown));
+    }
+
+    DispatchTimingReport {
+        iterations,
+        known_user: median(known_user_times),
+        unknown_user: median(unknown_user_times),
+    }
+}
+
+/// the slower of both divided by the faster one
+#[cfg(feature = "timing-test")]
+fn ratio(a: Duration, b: Duration) -> f64 {
+    let (a, b) = (a.as_secs_f64(), b.as_secs_f64());
+
+    a.max(b) / a.min(b).max(f64::MIN_POSITIVE)
+}
+
+#[cfg(feature = "timing-test")]
+fn median(mut times: Vec<Duration>) -> Duration {
+    times.sort_unstable();
+    times.get(times.len() / 2).copied().unwrap_or_default()
+}
+
+#[cfg(test)]
+#[cfg(feature = "group-1024")]
+mod tests {
+    use super::*;
+    use crate::{compute_user_secrets, HostAPI, Srp6};
+
+    #[test]
+    fn should_report_the_values_of_the_exchange() {
+        let inputs = ConformanceInputs::rfc5054_appendix_b();
+        let report = conformance_report::<128, 16>(&inputs).unwrap();
+        assert_eq!(report, conformance_report::<128, 16>(&inputs).unwrap());
+
+        let (_, v) = compute_user_secrets(
+            &inputs.constants.module,
+            &inputs.constants.generator,
+            inputs.salt.clone(),
+            &inputs.username,
+            &inputs.password,
+        );
+        assert_eq!(report.v, v.to_string());
+        assert_eq!(
+            report.k,
+            Srp6::<128, 16>::new()
+                .multiplier(&inputs.constants)
+                .to_string()
+        );
+
+        // A = g^a % N does not depend on the byte order of the hash
+        assert_eq!(
+            report.A,
+            "61D5E490F6F1B79547B0704C436F523DD0E560F0C64115BB72557EC44352E8903211C04692272D8B2D1A5358A2CF1B6E0BFCF99F921530EC8E39356179EAE45E42BA92AEACED825171E1E8B9AF6D9C03E1327F44BE087EF06530E69F66615261EEF54073CA11CF5858F0EDFDFE15EFEAB349EF5D76988A3672FAC47B0769447B"
+        );
+
+        // a host with the same inputs accepts the reported proof
+        let host = Srp6::<128, 16>::new();
+        let details = UserDetails {
+            username: inputs.username.clone(),
+            salt: inputs.salt.clone(),
+            verifier: v,
+        };
+        let user_handshake = UserHandshake {
+            username: inputs.username.clone(),
+            user_publickey: calculate_pubkey_A(
+                &inputs.constants.module,
+                &inputs.constants.generator,
+                &inputs.a,
+            ),
+        };
+        let b = ServerPrivateKey::from(inputs.b.clone());
+        let mut host = host
+            .reconstruct(&details, &user_handshake, &inputs.constants, b)
+            .unwrap();
+        let proof = Proof::from_hex_str_be(&report.M1).unwrap();
+        let strong_proof = host.verify_proof(&proof).unwrap();
+        assert_eq!(report.M2, strong_proof.to_string());
+    }
+}
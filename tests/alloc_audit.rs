@@ -0,0 +1,91 @@
+//! Audits that the host's `verify_proof` performs a fixed number of allocations,
+//! no matter if the given proof is valid, almost valid or garbage,
+//! and that too long hex input is refused before it is decoded.
+//!
+//! run with `cargo test --features alloc-audit --test alloc_audit`
+#![cfg(all(feature = "alloc-audit", feature = "group-1024"))]
+
+use std::alloc::{GlobalAlloc, Layout, System};
+use std::cell::Cell;
+use std::convert::TryFrom;
+
+use srp6::*;
+
+struct CountingAllocator;
+
+thread_local! {
+    static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+    unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+        ALLOCATIONS.with(|count| count.set(count.get() + 1));
+        System.alloc(layout)
+    }
+
+    unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+        System.dealloc(ptr, layout)
+    }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+fn allocations_of(f: impl FnOnce()) -> usize {
+    let before = ALLOCATIONS.with(Cell::get);
+    f();
+    ALLOCATIONS.with(Cell::get) - before
+}
+
+type Srp6_1024 = Srp6<128, 128>;
+type Srp6user1024 = Srp6User<128, 128>;
+
+#[test]
+fn verify_proof_should_allocate_a_fixed_amount() {
+    let constants = rfc5054_1024();
+    let mut host = Srp6_1024::new();
+    let mut user = Srp6user1024::new();
+    let details = host.generate_new_user_secrets("Bob", "secret-password", &constants);
+    let user_handshake = user.start_handshake("Bob", &constants);
+    let server_handshake = host
+        .continue_handshake(&details, &user_handshake, &constants)
+        .unwrap();
+    let proof = user
+        .update_handshake(&server_handshake, &constants, "Bob", "secret-password")
+        .unwrap();
+
+    let valid = allocations_of(|| assert!(host.verify_proof(&proof).is_ok()));
+
+    let mut first_byte_flipped = proof.to_vec();
+    first_byte_flipped[0] ^= 0x01;
+    let mut last_byte_flipped = proof.to_vec();
+    *last_byte_flipped.last_mut().unwrap() ^= 0x80;
+    let invalid_proofs = [
+        Proof::from(first_byte_flipped.as_slice()),
+        Proof::from(last_byte_flipped.as_slice()),
+        Proof::from([0xff_u8; 20]),
+        Proof::from([0xff_u8; 64]),
+    ];
+    let invalid: Vec<usize> = invalid_proofs
+        .iter()
+        .map(|p| allocations_of(|| assert!(host.verify_proof(p).is_err())))
+        .collect();
+
+    assert!(invalid.iter().all(|n| *n == invalid[0]), "{:?}", invalid);
+    // the only difference is the copy of the given proof inside `Srp6Error::InvalidProof`,
+    // which depends on the size of the given proof, but not on any secret
+    assert_eq!(valid + 1, invalid[0]);
+}
+
+#[test]
+fn too_long_hex_strings_should_be_refused_without_allocating() {
+    let huge = "f".repeat(10 * 1024 * 1024);
+
+    let allocations = allocations_of(|| {
+        assert!(PublicKey::from_hex_str_be_bounded(&huge, 128).is_err());
+        assert!(PublicKey::try_from(huge.as_str()).is_err());
+        assert!(StrongProof::from_hex_str_be(&huge).is_err());
+    });
+
+    assert_eq!(allocations, 0);
+}
@@ -0,0 +1,68 @@
+//! every public api that can fail returns a `Result`, so the whole exchange,
+//! from parsing untrusted input to the session key, runs without a single `unwrap`
+#![cfg(feature = "group-1024")]
+#![allow(non_snake_case)]
+use srp6::*;
+
+type Srp6_1024 = Srp6<128, 128>;
+type Srp6user1024 = Srp6User<128, 128>;
+
+/// the fallible apis of the exchange in order, each error is propagated with `?`
+fn exchange(a_hex: &str, password: &ClearTextPassword) -> Result<AuthenticatedSession> {
+    let constants = group_by_name("rfc5054-1024")?.require_safe_prime()?;
+    let mut host = Srp6_1024::new().require_security_level(80)?;
+    let details =
+        host.generate_new_user_secrets_checked("Bob", "secret-password", &constants, |p| {
+            p.len() >= 8
+        })?;
+    Srp6_1024::validate_user(&details)?;
+    Srp6_1024::validate_salt(&details.salt)?;
+
+    let mut user = Srp6user1024::new();
+    let a = ClientPrivateKey::from(PrivateKey::from_hex_str_be_bounded(a_hex, 128)?);
+    let user_handshake = user.start_handshake_with_private_key("Bob", &constants, a)?;
+    // `A` as received as hex, e.g. from a web client
+    let A = PublicKey::from_hex_str_be_bounded(&user_handshake.user_publickey.to_string(), 128)?;
+    Srp6_1024::precheck_public_key(&A, &constants)?;
+
+    let server_handshake = host.continue_handshake(&details, &user_handshake, &constants)?;
+    let proof = user.update_handshake(&server_handshake, &constants, "Bob", password)?;
+    let handshake_proof = HandshakeProof::from_concat_bytes::<128>(
+        &HandshakeProof::new::<128>(A, proof)?.to_concat_bytes::<128>(),
+    )?;
+    let session = host.verify_proof_authenticated(&handshake_proof.proof)?;
+    user.verify_proof(&session.strong_proof)?;
+    session.derive_key(b"fallible api", 32)?;
+
+    Ok(session)
+}
+
+const PRIVATE_KEY: &str = "60975527035CF2AD1989806F0407210BC81EDC04E2762A56AFD529DDDA2D4393";
+
+#[test]
+fn should_run_the_exchange_without_unwrap() {
+    match exchange(PRIVATE_KEY, "secret-password") {
+        Ok(session) => assert_eq!(session.username, "Bob"),
+        Err(e) => panic!("the exchange failed: {}", e),
+    }
+}
+
+#[test]
+fn should_propagate_every_error_as_srp6_error() {
+    let error = exchange(PRIVATE_KEY, "wrong-password").unwrap_err();
+    assert!(matches!(error.root_cause(), Srp6Error::InvalidProof(_)));
+    assert!(matches!(
+        exchange("not hex", "secret-password"),
+        Err(Srp6Error::InvalidNumber(BigNumberError::InvalidHexStr))
+    ));
+    assert!(matches!(
+        exchange(&"ab".repeat(129), "secret-password"),
+        Err(Srp6Error::InvalidNumber(
+            BigNumberError::KeyLengthMismatch { .. }
+        ))
+    ));
+    assert_eq!(
+        exchange("00", "secret-password").err(),
+        Some(Srp6Error::InvalidPrivateKey)
+    );
+}
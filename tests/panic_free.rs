@@ -0,0 +1,135 @@
+//! pathological user controlled inputs (username, password, salt, `A`, `M1`)
+//! must end in an error or a failed proof, never in a panic
+#![cfg(feature = "group-1024")]
+#![allow(non_snake_case)]
+use srp6::*;
+
+type Srp6_1024 = Srp6<128, 128>;
+type Srp6user1024 = Srp6User<128, 128>;
+
+/// runs the full exchange and returns if the user was authenticated
+fn authenticate(details: &UserDetails, I: UsernameRef, p: &ClearTextPassword) -> Result<()> {
+    let constants = rfc5054_1024();
+    let (mut host, mut user) = (Srp6_1024::new(), Srp6user1024::new());
+    let user_handshake = user.start_handshake(I, &constants);
+    let server_handshake = host.continue_handshake(details, &user_handshake, &constants)?;
+    let proof = user.update_handshake(&server_handshake, &constants, I, p)?;
+    let strong_proof = host.verify_proof(&proof)?;
+
+    user.verify_proof(&strong_proof).map(|_| ())
+}
+
+#[test]
+fn should_authenticate_non_ascii_and_long_credentials() {
+    let constants = rfc5054_1024();
+    for (I, p) in [
+        ("Bøb 🦀", "pässwörd"),
+        ("\0", "\0"),
+        (&"B".repeat(10_000)[..], &"p".repeat(10_000)[..]),
+    ] {
+        let details = Srp6_1024::new().generate_new_user_secrets(I, p, &constants);
+        assert!(authenticate(&details, I, p).is_ok());
+    }
+}
+
+#[test]
+fn should_handle_salts_of_any_length() {
+    let constants = rfc5054_1024();
+    for salt in [
+        Salt::default(),
+        Salt::from([0xFF; 128]),
+        Salt::from([0xFF; 300]),
+    ] {
+        let mut details = Srp6_1024::new().generate_new_user_secrets("Bob", "pw", &constants);
+        details.salt = salt;
+        // the verifier does not match the replaced salt
+        assert!(authenticate(&details, "Bob", "pw").is_err());
+    }
+}
+
+#[test]
+fn should_reject_pathological_public_keys() {
+    let constants = rfc5054_1024();
+    let details = Srp6_1024::new().generate_new_user_secrets("Bob", "pw", &constants);
+    for A in [
+        PublicKey::default(),
+        constants.module.clone(),
+        PublicKey::from([0xFF; 128]),
+        PublicKey::from([0xFF; 300]),
+    ] {
+        let mut host = Srp6_1024::new();
+        let user_handshake = UserHandshake {
+            username: "Bob".to_owned(),
+            user_publickey: A,
+        };
+        if host
+            .continue_handshake(&details, &user_handshake, &constants)
+            .is_ok()
+        {
+            assert!(host.verify_proof(&Proof::from([0x42; 20])).is_err());
+        }
+    }
+}
+
+#[test]
+fn should_reject_pathological_proofs() {
+    let constants = rfc5054_1024();
+    let details = Srp6_1024::new().generate_new_user_secrets("Bob", "pw", &constants);
+    let user_handshake = Srp6user1024::new().start_handshake("Bob", &constants);
+    let mut host = Srp6_1024::new();
+    host.continue_handshake(&details, &user_handshake, &constants)
+        .unwrap();
+
+    for proof in [
+        Proof::default(),
+        Proof::from([0xFF; 20]),
+        Proof::from([0xFF; 21]),
+        Proof::from([0xFF; 4096]),
+    ] {
+        assert!(host.verify_proof(&proof).is_err());
+    }
+}
+
+#[test]
+fn should_reject_a_handshake_of_another_user() {
+    let constants = rfc5054_1024();
+    let details = Srp6_1024::new().generate_new_user_secrets("Bob", "pw", &constants);
+    let user_handshake = Srp6user1024::new().start_handshake("Alice", &constants);
+
+    assert_eq!(
+        Srp6_1024::new()
+            .continue_handshake(&details, &user_handshake, &constants)
+            .err(),
+        Some(Srp6Error::UsernameMismatch)
+    );
+}
+
+#[test]
+fn should_reject_a_group_that_does_not_match_the_key_length() {
+    let constants = OpenConstants {
+        module: PrimeModulus::from(23),
+        generator: Generator::from(5),
+    };
+    let details = Srp6_1024::new().generate_new_user_secrets("Bob", "pw", &constants);
+    let mut user = Srp6user1024::new();
+    let user_handshake = user.start_handshake("Bob", &constants);
+
+    let error = Srp6_1024::new()
+        .continue_handshake(&details, &user_handshake, &constants)
+        .unwrap_err();
+    assert!(matches!(
+        error.root_cause(),
+        Srp6Error::KeyLengthMismatch { .. }
+    ));
+    let server_handshake = ServerHandshake {
+        salt: details.salt,
+        server_publickey: PublicKey::from(7),
+    };
+    let error = user
+        .update_handshake(&server_handshake, &constants, "Bob", "pw")
+        .unwrap_err();
+    assert!(matches!(
+        error.root_cause(),
+        Srp6Error::KeyLengthMismatch { .. }
+    ));
+}
@@ -0,0 +1,57 @@
+//! property based tests of the full exchange between client and server
+#![cfg(feature = "group-1024")]
+use proptest::prelude::*;
+use srp6::*;
+
+type Srp6_1024 = Srp6<128, 128>;
+type Srp6user1024 = Srp6User<128, 128>;
+
+/// runs step 1 to 4 and returns the proof `M1` of the client
+fn exchange(
+    host: &mut Srp6_1024,
+    user: &mut Srp6user1024,
+    username: &str,
+    password: &str,
+) -> Proof {
+    let constants = rfc5054_1024();
+    let details = Srp6_1024::new().generate_new_user_secrets(username, password, &constants);
+    let user_handshake = user.start_handshake(username, &constants);
+    let server_handshake = host
+        .continue_handshake(&details, &user_handshake, &constants)
+        .unwrap();
+
+    user.update_handshake(&server_handshake, &constants, username, password)
+        .unwrap()
+}
+
+proptest! {
+    #![proptest_config(ProptestConfig::with_cases(32))]
+
+    #[test]
+    fn should_always_authenticate_mutually(username in "\\PC{0,32}", password in "\\PC{0,32}") {
+        let (mut host, mut user) = (Srp6_1024::new(), Srp6user1024::new());
+        let proof = exchange(&mut host, &mut user, &username, &password);
+
+        let strong_proof = host.verify_proof(&proof).unwrap();
+        let (verified_proof, _) = user.verify_proof(&strong_proof).unwrap();
+        prop_assert_eq!(verified_proof, strong_proof);
+    }
+
+    #[test]
+    fn should_reject_any_single_bit_flip_of_the_proof(
+        username in "\\PC{1,32}",
+        password in "\\PC{1,32}",
+        bit in 0..160_usize,
+    ) {
+        let (mut host, mut user) = (Srp6_1024::new(), Srp6user1024::new());
+        let proof = exchange(&mut host, &mut user, &username, &password);
+
+        let mut corrupted = proof.to_array_pad_zero::<20>();
+        corrupted[bit / 8] ^= 1 << (bit % 8);
+        let corrupted = Proof::from(corrupted);
+        prop_assert_eq!(
+            host.verify_proof(&corrupted),
+            Err(Srp6Error::InvalidProof(corrupted))
+        );
+    }
+}
@@ -0,0 +1,27 @@
+//! Smoke tests that rejecting a proof of a wrong password takes as long as
+//! rejecting a malformed proof, and that a handshake for an unknown user takes as
+//! long as one for a known user, the tolerance is generous as timings are noisy
+//!
+//! run with `cargo test --release --features timing-test --test timing`
+#![cfg(feature = "timing-test")]
+
+use srp6::testing::{dispatch_timing_check, timing_check};
+
+/// the medians may differ by half, a leak of the proof check would be far larger
+const TOLERANCE: f64 = 0.5;
+
+#[test]
+fn should_reject_wrong_and_malformed_proofs_in_similar_time() {
+    let report = timing_check(2_000);
+
+    assert_eq!(report.iterations, 2_000);
+    assert!(report.is_within(TOLERANCE), "{:?}", report);
+}
+
+#[test]
+fn should_answer_known_and_unknown_users_in_similar_time() {
+    let report = dispatch_timing_check(200);
+
+    assert_eq!(report.iterations, 200);
+    assert!(report.is_within(TOLERANCE), "{:?}", report);
+}
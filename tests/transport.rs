@@ -0,0 +1,110 @@
+//! the full exchange over an in-memory duplex pipe, client and server in their own thread
+#![cfg(all(feature = "transport", feature = "group-1024"))]
+use std::io::{Read, Result, Write};
+use std::sync::mpsc::{channel, Receiver, Sender};
+use std::thread;
+
+use srp6::transport::{client_authenticate, server_authenticate};
+use srp6::*;
+
+type Srp6_1024 = Srp6<128, 128>;
+type Srp6user1024 = Srp6User<128, 128>;
+
+/// one end of a duplex pipe, each write is sent as one chunk to the other end
+struct Pipe {
+    tx: Sender<Vec<u8>>,
+    rx: Receiver<Vec<u8>>,
+    buffer: Vec<u8>,
+}
+
+fn duplex() -> (Pipe, Pipe) {
+    let (a_tx, b_rx) = channel();
+    let (b_tx, a_rx) = channel();
+    let pipe = |tx, rx| Pipe {
+        tx,
+        rx,
+        buffer: Vec::new(),
+    };
+
+    (pipe(a_tx, a_rx), pipe(b_tx, b_rx))
+}
+
+impl Read for Pipe {
+    fn read(&mut self, buf: &mut [u8]) -> Result<usize> {
+        if self.buffer.is_empty() {
+            match self.rx.recv() {
+                Ok(chunk) => self.buffer = chunk,
+                // the other end is gone
+                Err(_) => return Ok(0),
+            }
+        }
+        let n = buf.len().min(self.buffer.len());
+        buf[..n].copy_from_slice(&self.buffer[..n]);
+        self.buffer.drain(..n);
+
+        Ok(n)
+    }
+}
+
+impl Write for Pipe {
+    fn write(&mut self, buf: &[u8]) -> Result<usize> {
+        self.tx.send(buf.to_vec()).map_err(|_| {
+            std::io::Error::new(std::io::ErrorKind::BrokenPipe, "the other end is gone")
+        })?;
+
+        Ok(buf.len())
+    }
+
+    fn flush(&mut self) -> Result<()> {
+        Ok(())
+    }
+}
+
+fn authenticate(
+    password: &'static str,
+) -> (
+    srp6::Result<StrongSessionKey>,
+    srp6::Result<StrongSessionKey>,
+) {
+    let constants = rfc5054_1024();
+    let mut user_store = HashMapUserStore::new();
+    user_store.store(Srp6_1024::new().generate_new_user_secrets(
+        "Bob",
+        "secret-password",
+        &constants,
+    ));
+    let (mut client_end, mut server_end) = duplex();
+
+    let server = thread::spawn(move || {
+        server_authenticate(
+            &mut server_end,
+            &user_store,
+            &mut Srp6_1024::new(),
+            &rfc5054_1024(),
+        )
+    });
+    let client = client_authenticate(
+        &mut client_end,
+        "Bob",
+        password,
+        &mut Srp6user1024::new(),
+        &constants,
+    );
+
+    (client, server.join().unwrap())
+}
+
+#[test]
+fn should_authenticate_over_a_stream() {
+    let (client, server) = authenticate("secret-password");
+
+    assert_eq!(client.unwrap(), server.unwrap());
+}
+
+#[test]
+fn should_fail_on_both_ends_for_a_wrong_password() {
+    let (client, server) = authenticate("wrong-password");
+
+    assert!(matches!(client, Err(Srp6Error::InvalidStrongProof(_))));
+    assert!(matches!(server, Err(Srp6Error::InvalidProof(_))));
+}